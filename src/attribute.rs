@@ -20,10 +20,63 @@ impl Attributes {
             .intervals
             .iter()
             .filter(|&x| *x != i)
-            .map(|&x| x)
+            .copied()
             .collect::<Vec<u8>>();
     }
 
+    // Whether intervals form a stack of given step from root,
+    // allowing one alternative step anywhere in the stack
+    fn is_stacked(&self, step: u8, alt: u8) -> bool {
+        let n = self.intervals.len();
+
+        (0..n).any(|alt_pos| {
+            let mut pc = 0;
+            let mut stack = vec![0];
+            for i in 0..n - 1 {
+                pc = (pc + if i == alt_pos { alt } else { step }) % 12;
+                stack.push(pc);
+            }
+            stack.iter().all(|x| self.intervals.contains(x))
+                && self.intervals.iter().all(|x| stack.contains(x))
+        })
+    }
+
+    // Whether intervals are adjacent seconds up from root,
+    // spanning less than a tritone
+    fn is_cluster(&self) -> bool {
+        let mut sorted = self.intervals.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        sorted.len() > 2
+            && sorted[0] == 0
+            && sorted[sorted.len() - 1] < 6
+            && sorted.windows(2).all(|w| w[1] - w[0] <= 2)
+    }
+
+    /// Resolves chords of three to five voices stacked in fourths,
+    /// fifths or seconds, which have no sensible tertian name.
+    /// Not part of resolve, as the same notes often make common
+    /// tertian chords (eg. Gsus4(7no5) and G C F).
+    pub fn resolve_stacked(&self) -> Option<String> {
+        let voices = self.intervals.len();
+
+        if !(3..=5).contains(&voices) {
+            None
+        } else if self.is_stacked(5, 6) {
+            Some(format!(" quartal ({} voices)", voices))
+        }
+        // three stacked fifths are a sus2 chord,
+        // and fifths stacked up to a third are pentatonic 6/9 chords
+        else if voices > 3 && !self.has(&[3]) && !self.has(&[4]) && self.is_stacked(7, 6) {
+            Some(format!(" quintal ({} voices)", voices))
+        } else if !self.has(&[7]) && self.is_cluster() {
+            Some(format!(" cluster ({} voices)", voices))
+        } else {
+            None
+        }
+    }
+
     fn resolve_nth(&self) -> String {
         let mut val = String::new();
 
@@ -31,7 +84,7 @@ impl Attributes {
         if self.has(&[10]) || self.has(&[11]) {
             // M prefix
             if self.has(&[11]) {
-                val.push_str("M");
+                val.push('M');
             }
 
             // 13
@@ -44,7 +97,7 @@ impl Attributes {
             }
             // 9
             else if self.has(&[2]) {
-                val.push_str("9");
+                val.push('9');
 
                 if self.has(&[9]) {
                     val.push_str("add13");
@@ -60,7 +113,7 @@ impl Attributes {
             }
             // 7
             else {
-                val.push_str("7");
+                val.push('7');
 
                 if self.has(&[5]) {
                     val.push_str("add11");
@@ -110,13 +163,13 @@ impl Attributes {
                 }
                 // m
                 else {
-                    val.push_str("m");
+                    val.push('m');
                 }
             }
 
             // 6
             if &val != "dim" && self.has(&[7, 9]) {
-                val.push_str("6");
+                val.push('6');
                 self.remove(9);
             }
         }
@@ -132,7 +185,7 @@ impl Attributes {
         }
         // 5
         else if self.has(&[7]) {
-            val.push_str("5");
+            val.push('5');
         } else {
             has3 = false;
         }
@@ -151,12 +204,12 @@ impl Attributes {
         let nth = self.resolve_nth();
 
         //additional markings
-        if nth.len() > 0 || !has5 || !has3 {
-            val.push_str("(");
+        if !nth.is_empty() || !has5 || !has3 {
+            val.push('(');
         }
 
         // nth
-        if nth.len() > 0 {
+        if !nth.is_empty() {
             val.push_str(nth.as_ref());
         }
 
@@ -176,10 +229,48 @@ impl Attributes {
         }
 
         //close add. markings
-        if nth.len() > 0 || !has5 || !has3 {
-            val.push_str(")");
+        if !nth.is_empty() || !has5 || !has3 {
+            val.push(')');
         }
 
         val
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(intervals: &[u8]) -> Attributes {
+        let mut attr = Attributes::new();
+        for &i in intervals {
+            attr.push_interval(i);
+        }
+        attr
+    }
+
+    #[test]
+    fn stacked_structures() {
+        assert_eq!(
+            attributes(&[0, 5, 10, 3]).resolve_stacked().as_deref(),
+            Some(" quartal (4 voices)")
+        );
+        assert_eq!(
+            attributes(&[0, 7, 2, 9]).resolve_stacked().as_deref(),
+            Some(" quintal (4 voices)")
+        );
+        assert_eq!(
+            attributes(&[0, 2, 4]).resolve_stacked().as_deref(),
+            Some(" cluster (3 voices)")
+        );
+        assert_eq!(attributes(&[0, 4, 7]).resolve_stacked(), None);
+        // spans a tritone
+        assert_eq!(attributes(&[0, 1, 3, 5, 6]).resolve_stacked(), None);
+    }
+
+    #[test]
+    fn stacks_keep_tertian_names() {
+        assert_eq!(attributes(&[0, 2, 4]).resolve(), "(add9no5)");
+        assert_eq!(attributes(&[0, 5, 10]).resolve(), "sus4(7no5)");
+    }
+}
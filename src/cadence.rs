@@ -0,0 +1,246 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::progression::{Progression, ProgressionChord};
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Cadence {
+    Authentic,
+    Plagal,
+    Half,
+    Deceptive,
+}
+
+impl From<Cadence> for String {
+    fn from(cadence: Cadence) -> Self {
+        match cadence {
+            Cadence::Authentic => "authentic",
+            Cadence::Plagal => "plagal",
+            Cadence::Half => "half",
+            Cadence::Deceptive => "deceptive",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Cadence {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "plagal" => Cadence::Plagal,
+            "half" => Cadence::Half,
+            "deceptive" => Cadence::Deceptive,
+            _ => Cadence::Authentic,
+        }
+    }
+}
+
+impl Cadence {
+    /// Scale degrees of the closing chords, tonic being 0
+    /// (eg. V I -> 4, 0)
+    pub fn degrees(self) -> [usize; 2] {
+        match self {
+            Cadence::Authentic => [4, 0],
+            Cadence::Plagal => [3, 0],
+            Cadence::Half => [3, 4],
+            Cadence::Deceptive => [4, 5],
+        }
+    }
+}
+
+pub fn supported_cadences() -> Vec<String> {
+    ["authentic", "plagal", "half", "deceptive"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct CadencePoint {
+    /// Index of the closing chord in progression
+    pub index: usize,
+    /// Perfect authentic, imperfect authentic, plagal,
+    /// half, phrygian half or deceptive
+    pub kind: String,
+    /// Roman numerals of the cadence chords
+    pub numerals: Vec<String>,
+}
+
+impl fmt::Display for CadencePoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:>3}  {1:<20} {2:}",
+            self.index + 1,
+            &self.kind,
+            &self.numerals.join(" ")
+        )
+    }
+}
+
+// Chord in key: semitones of root above tonic, intervals and whether
+// the root is in bass
+struct Degree {
+    root: u8,
+    intervals: Vec<u8>,
+    root_position: bool,
+    first_inversion: bool,
+}
+
+impl Degree {
+    fn new(chord: &ProgressionChord, tonic: u8) -> Degree {
+        let root = util::pitch_class(chord.chord.root());
+        let intervals = chord.chord.intervals();
+        let bass = chord
+            .bass
+            .as_ref()
+            .map(|b| (util::pitch_class(util::str_to_note(b)) + 12 - root) % 12);
+
+        Degree {
+            root: (root + 12 - tonic) % 12,
+            root_position: bass.is_none_or(|b| b == 0),
+            first_inversion: bass.is_some_and(|b| b == 3 || b == 4),
+            intervals,
+        }
+    }
+
+    fn has(&self, interval: u8) -> bool {
+        self.intervals.contains(&interval)
+    }
+
+    // Major or minor triad, sevenths included
+    fn is_tonic(&self) -> bool {
+        self.root == 0 && self.has(7) && (self.has(3) || self.has(4))
+    }
+
+    // Major triad or dominant seventh on the fifth degree
+    fn is_dominant(&self) -> bool {
+        self.root == 7 && self.has(4) && !self.has(11)
+    }
+
+    // Diminished chord on the leading tone
+    fn is_leading(&self) -> bool {
+        self.root == 11 && self.has(3) && self.has(6)
+    }
+}
+
+/// Marks cadences of progression: authentic cadences are perfect
+/// with both chords in root position, half cadences end a progression
+/// or a bar long dominant that doesn't resolve.
+pub fn detect(progression: &Progression) -> Vec<CadencePoint> {
+    let key = progression.key_notes();
+    let tonic = util::pitch_class(key[0]);
+    let minor = (util::pitch_class(key[2]) + 12 - tonic) % 12 == 3;
+    let chords = &progression.chords;
+    let degrees: Vec<Degree> = chords.iter().map(|c| Degree::new(c, tonic)).collect();
+
+    let mut result = vec![];
+
+    for i in 1..chords.len() {
+        let (a, b) = (&degrees[i - 1], &degrees[i]);
+        let next = degrees.get(i + 1);
+
+        let kind = if b.is_tonic() && a.is_dominant() {
+            if a.root_position && b.root_position {
+                "perfect authentic"
+            } else {
+                "imperfect authentic"
+            }
+        } else if b.is_tonic() && a.is_leading() {
+            "imperfect authentic"
+        } else if b.is_tonic() && a.root == 5 {
+            "plagal"
+        } else if a.is_dominant() && (b.root == 8 || b.root == 9) && !b.has(6) {
+            "deceptive"
+        } else if b.is_dominant()
+            && next.is_none_or(|n| {
+                chords[i].beats >= 4.0 && !n.is_tonic() && n.root != 8 && n.root != 9
+            })
+        {
+            if minor && a.root == 5 && a.has(3) && a.first_inversion {
+                "phrygian half"
+            } else {
+                "half"
+            }
+        } else {
+            continue;
+        };
+
+        result.push(CadencePoint {
+            index: i,
+            kind: kind.to_string(),
+            numerals: chords[i - 1..=i]
+                .iter()
+                .map(|c| c.symbol(Style::Roman, &key))
+                .collect(),
+        });
+    }
+
+    result
+}
+
+/// Marks cadences of chords in key and scale, all in root position
+pub fn detect_chords(chords: &[Chord], key: &str, scale: &str) -> Vec<CadencePoint> {
+    detect(&Progression {
+        key: key.to_string(),
+        scale: scale.to_string(),
+        chords: chords
+            .iter()
+            .map(|c| ProgressionChord {
+                numeral: c.name.clone(),
+                chord: c.clone(),
+                bass: None,
+                beats: 4.0,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cadence(numerals: &str) -> Vec<(usize, String)> {
+        detect(&Progression::parse(numerals, "C", "major").unwrap())
+            .into_iter()
+            .map(|c| (c.index, c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn perfect_authentic() {
+        assert_eq!(cadence("I IV V I"), vec![(3, "perfect authentic".into())]);
+    }
+
+    #[test]
+    fn imperfect_authentic() {
+        assert_eq!(cadence("I V6 I"), vec![(2, "imperfect authentic".into())]);
+        assert_eq!(cadence("I vii° I"), vec![(2, "imperfect authentic".into())]);
+    }
+
+    #[test]
+    fn plagal() {
+        assert_eq!(cadence("I IV I"), vec![(2, "plagal".into())]);
+    }
+
+    #[test]
+    fn half() {
+        assert_eq!(cadence("I ii V"), vec![(2, "half".into())]);
+    }
+
+    #[test]
+    fn deceptive() {
+        assert_eq!(cadence("I V vi"), vec![(2, "deceptive".into())]);
+    }
+
+    #[test]
+    fn phrygian_half() {
+        let points = detect(&Progression::parse("i iv6 V", "A", "minor").unwrap());
+
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].kind, "phrygian half");
+        assert_eq!(points[0].numerals, vec!["iv6", "V"]);
+    }
+}
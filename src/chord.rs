@@ -1,7 +1,9 @@
 use std::fmt;
 
 use crate::attribute::Attributes;
+use crate::naming::{self, Style};
 use crate::scale;
+use crate::tension::{Spectrum, Tension};
 use crate::util;
 use serde_derive::{Deserialize, Serialize};
 
@@ -12,41 +14,81 @@ pub struct Chord {
     pub extended: bool,
     pub weight: i8,
     pub valid: bool,
+    /// Missing from chords serialized before tension metrics
+    #[serde(default)]
+    pub tension: Tension,
 }
 
 impl Chord {
     ///Constructor from given root note and interval vec
-    pub fn new(root: &String, intervals: Vec<u8>, extended: bool, weight: i8) -> Chord {
+    pub fn new(root: &str, intervals: Vec<u8>, extended: bool, weight: i8) -> Chord {
         let mut name = String::new();
         let mut notes = vec![];
 
         name.push_str(&root.to_uppercase());
 
-        let root_note = util::str_to_note(&root);
+        let root_note = util::str_to_note(root);
         let chromatic = scale::chromatic_notes(root_note);
         let mut attr = Attributes::new();
 
         //Pick notes from chromatic scale according to interval values
         for interval in intervals.iter() {
             notes.push(util::note_to_str(chromatic[*interval as usize]));
-            attr.push_interval(*interval as u8);
+            attr.push_interval(*interval);
         }
 
         //Push attributes to name
         name.push_str(attr.resolve().as_ref());
         let valid = !name.contains("no5no3") && !name.contains("inv3s");
+        // permutations left out of analysis aren't measured
+        let tension = if valid {
+            Tension::new(
+                util::pitch_class(root_note),
+                &intervals,
+                &Spectrum::default(),
+            )
+        } else {
+            Tension::default()
+        };
 
         Chord {
-            name: name,
-            notes: notes,
-            extended: extended,
-            weight: weight,
-            valid: valid,
+            name,
+            notes,
+            extended,
+            weight,
+            valid,
+            tension,
         }
     }
 
+    /// Constructor from root note and intervals outside of scale analysis.
+    /// Notes are spelled after given notes when possible,
+    /// otherwise following the accidental of root.
+    pub fn from_intervals(root: (char, i8), intervals: Vec<u8>, spelling: &[(char, i8)]) -> Chord {
+        let mut chord = Chord::new(&util::note_to_str(root), intervals, false, 0);
+
+        let mut src = vec![root];
+        src.extend(
+            spelling
+                .iter()
+                .filter(|&&n| util::pitch_class(n) != util::pitch_class(root)),
+        );
+
+        for el in chord.notes.iter_mut() {
+            let pc = util::pitch_class(util::str_to_note(el));
+            *el = util::note_to_str(util::spell_pitch_class(pc, &src, root.1 < 0));
+        }
+
+        chord
+    }
+
+    /// Measures tension with given harmonic spectrum
+    pub fn measure(&mut self, spectrum: &Spectrum) {
+        self.tension = Tension::new(util::pitch_class(self.root()), &self.intervals(), spectrum);
+    }
+
     // Formats notes according to given src of notes
-    pub fn format_notes(&mut self, src: &Vec<(char, i8)>) {
+    pub fn format_notes(&mut self, src: &[(char, i8)]) {
         let strings = src
             .iter()
             .map(|&note| util::note_to_str(note))
@@ -58,6 +100,43 @@ impl Chord {
         }
     }
 
+    /// Returns root note of chord
+    pub fn root(&self) -> (char, i8) {
+        util::str_to_note(&self.notes[0])
+    }
+
+    /// Returns pitch classes of chord notes, root first
+    pub fn pitch_classes(&self) -> Vec<u8> {
+        self.notes
+            .iter()
+            .map(|n| util::pitch_class(util::str_to_note(n)))
+            .collect()
+    }
+
+    /// Returns chord intervals as semitones above root
+    pub fn intervals(&self) -> Vec<u8> {
+        let pcs = self.pitch_classes();
+        pcs.iter().map(|pc| (pc + 12 - pcs[0]) % 12).collect()
+    }
+
+    /// Returns chord spelling for given pitch class
+    pub fn spell(&self, pc: u8) -> (char, i8) {
+        self.notes
+            .iter()
+            .map(|n| util::str_to_note(n))
+            .find(|&n| util::pitch_class(n) == pc % 12)
+            .unwrap_or_else(|| util::pitch_class_to_note(pc))
+    }
+
+    /// Returns chord name in given naming style.
+    /// Key notes, tonic first, give the context for roman numerals.
+    pub fn styled_name(&self, style: Style, key: &[(char, i8)]) -> String {
+        match style {
+            Style::Default => self.name.clone(),
+            _ => naming::name(self.root(), &self.intervals(), style, key),
+        }
+    }
+
     pub fn equals(&self, other: &Chord) -> bool {
         if self.notes.len() != other.notes.len() {
             return false;
@@ -93,3 +172,24 @@ impl fmt::Display for Chord {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_chord_without_tension() {
+        let json = r#"{"name":"C","notes":["c","e","g"],"extended":false,"weight":0,"valid":true}"#;
+        let chord: Chord = serde_json::from_str(json).unwrap();
+        assert_eq!(chord.notes, vec!["c", "e", "g"]);
+    }
+
+    #[test]
+    fn measures_valid_chords_only() {
+        let valid = Chord::new("c", vec![0, 4, 7], false, 0);
+        assert!(valid.valid && valid.tension != Tension::default());
+
+        let invalid = Chord::new("c", vec![0, 1], false, 0);
+        assert!(!invalid.valid && invalid.tension == Tension::default());
+    }
+}
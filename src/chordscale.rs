@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::scale;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ChordScale {
+    /// Scale name, starting from chord root
+    pub scale: String,
+    pub notes: Vec<String>,
+    /// Available tensions, as degrees above chord root
+    pub tensions: Vec<String>,
+    /// Scale notes a half step above a chord tone
+    pub avoid: Vec<String>,
+    /// Scale notes not found in key, empty without key context
+    pub outside: Vec<String>,
+}
+
+impl fmt::Display for ChordScale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<22} {1:<22} tensions: {2:<14} avoid: {3:}",
+            scale::friendly_name(&self.scale),
+            &self
+                .notes
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(" "),
+            &self.tensions.join(", "),
+            &self
+                .avoid
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+// Avoid note: non chord tone a half step above a chord tone,
+// apart from b9 of dominant chords
+fn is_avoid(interval: u8, intervals: &[u8]) -> bool {
+    let dominant = intervals.contains(&4) && intervals.contains(&10);
+
+    intervals.contains(&((interval + 11) % 12)) && !(dominant && interval == 1)
+}
+
+// Builds chord scale of scale notes, first note being the chord root
+fn chord_scale(chord: &Chord, name: &str, notes: &[(char, i8)], key: &[u8]) -> ChordScale {
+    let root = util::pitch_class(chord.root());
+    let intervals = chord.intervals();
+    let steps: Vec<u8> = notes
+        .iter()
+        .map(|&n| (util::pitch_class(n) + 12 - root) % 12)
+        .collect();
+    let note_str = |i: u8| util::note_to_str(notes[steps.iter().position(|&s| s == i).unwrap()]);
+
+    let extra: Vec<u8> = steps
+        .iter()
+        .copied()
+        .filter(|i| !intervals.contains(i))
+        .collect();
+
+    ChordScale {
+        scale: name.to_string(),
+        notes: notes.iter().map(|&n| util::note_to_str(n)).collect(),
+        tensions: extra
+            .iter()
+            .filter(|&&i| !is_avoid(i, &intervals))
+            .map(|&i| util::tension_label(i))
+            .collect(),
+        avoid: extra
+            .iter()
+            .filter(|&&i| is_avoid(i, &intervals))
+            .map(|&i| note_str(i))
+            .collect(),
+        outside: if key.is_empty() {
+            vec![]
+        } else {
+            steps
+                .iter()
+                .filter(|&&i| !key.contains(&((root + i) % 12)))
+                .map(|&i| note_str(i))
+                .collect()
+        },
+    }
+}
+
+/// Returns scales containing chord, starting from chord root,
+/// ranked by number of avoid notes
+pub fn chord_scales(chord: &Chord) -> Vec<ChordScale> {
+    chord_scales_with(chord, &[], "")
+}
+
+/// Returns scales containing chord, ranked by fit to key notes
+/// and then by number of avoid notes.
+/// Mode of the key scale on chord root is included when not in catalogue.
+pub fn chord_scales_in(chord: &Chord, key: &str, scale: &str) -> Vec<ChordScale> {
+    chord_scales_with(chord, &crate::scale_notes(key, scale), scale)
+}
+
+fn chord_scales_with(chord: &Chord, key: &[(char, i8)], key_scale: &str) -> Vec<ChordScale> {
+    let root = chord.root();
+    let pcs = chord.pitch_classes();
+    let key_pcs: Vec<u8> = key.iter().map(|&n| util::pitch_class(n)).collect();
+
+    let mut result: Vec<ChordScale> = scale::supported_scales()
+        .iter()
+        .filter(|name| !scale::is_directional(name))
+        .map(|name| {
+            let notes = crate::scale_notes(&util::note_to_str(root), name);
+            (name, notes)
+        })
+        .filter(|(_, notes)| {
+            let scale_pcs: Vec<u8> = notes.iter().map(|&n| util::pitch_class(n)).collect();
+            pcs.iter().all(|pc| scale_pcs.contains(pc))
+        })
+        .map(|(name, notes)| chord_scale(chord, name, &notes, &key_pcs))
+        .collect();
+
+    // key scale from chord root
+    let degree = key_pcs.iter().position(|&pc| pc == util::pitch_class(root));
+    if let Some(degree) = degree {
+        let diatonic = pcs.iter().all(|pc| key_pcs.contains(pc));
+        let listed = result.iter().any(|s| s.outside.is_empty());
+
+        if diatonic && !listed {
+            let mut notes = key[degree..].to_vec();
+            notes.extend(&key[..degree]);
+            let name = format!("{} mode {}", scale::friendly_name(key_scale), degree + 1);
+            result.push(chord_scale(chord, &name, &notes, &key_pcs));
+        }
+    }
+
+    // stable sort keeps catalogue order on ties
+    result.sort_by_key(|s| (s.outside.len(), s.avoid.len()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominant_in_major_key_is_mixolydian() {
+        let c7 = Chord::from_intervals(('c', 0), vec![0, 4, 7, 10], &[]);
+        let scales = chord_scales_in(&c7, "f", "major");
+
+        assert_eq!(scales[0].scale, "mixolydian");
+        assert!(scales[0].outside.is_empty());
+        assert_eq!(scales[0].avoid, vec!["f"]);
+        assert_eq!(scales[0].tensions, vec!["9", "13"]);
+    }
+
+    #[test]
+    fn flat_nine_is_not_avoided_on_dominants() {
+        let dominant = [0, 4, 7, 10];
+        let major = [0, 4, 7, 11];
+
+        assert!(!is_avoid(1, &dominant));
+        assert!(is_avoid(1, &major));
+        // 11 sits a half step above the major 3rd
+        assert!(is_avoid(5, &dominant));
+        assert!(!is_avoid(2, &dominant));
+    }
+}
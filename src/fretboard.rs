@@ -0,0 +1,409 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::instrument::{self, Instrument};
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+pub struct ShapeOptions {
+    pub instrument: Instrument,
+    pub capo: u8,
+    /// Highest fret above capo to search, limited by instrument's frets
+    pub frets: u8,
+    /// Largest fret span of fretted notes
+    pub max_span: u8,
+    /// Tones that must sound, as semitones above root.
+    /// When unset, all chord tones are required except
+    /// the perfect 5th of four or more note chords.
+    pub required: Option<Vec<u8>>,
+}
+
+impl Default for ShapeOptions {
+    fn default() -> Self {
+        ShapeOptions {
+            instrument: instrument::get_instrument("guitar")
+                .expect("Failed to find guitar profile"),
+            capo: 0,
+            frets: 12,
+            max_span: 4,
+            required: None,
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Fingering {
+    pub chord: String,
+    /// Fret per string above capo, lowest string first.
+    /// None for muted string, 0 for open string.
+    pub frets: Vec<Option<u8>>,
+    pub notes: Vec<Option<String>>,
+    pub capo: u8,
+    pub score: i32,
+}
+
+impl fmt::Display for Fingering {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = if self.frets.iter().any(|&f| f.unwrap_or(0) > 9) {
+            "-"
+        } else {
+            ""
+        };
+
+        write!(
+            f,
+            "{}",
+            self.frets
+                .iter()
+                .map(|f| f.map_or("x".to_string(), |f| f.to_string()))
+                .collect::<Vec<String>>()
+                .join(separator)
+        )?;
+
+        if self.capo > 0 {
+            write!(f, " (capo {})", self.capo)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Fingering {
+    /// Renders fingering as ascii chord diagram
+    pub fn diagram(&self) -> String {
+        let fretted: Vec<u8> = self
+            .frets
+            .iter()
+            .filter_map(|&f| f)
+            .filter(|&f| f > 0)
+            .collect();
+        let lowest = fretted.iter().min().copied().unwrap_or(1);
+        let highest = fretted.iter().max().copied().unwrap_or(1);
+
+        // diagram starts from nut when shape fits in first frets
+        let first = if highest <= 4 { 1 } else { lowest };
+        let rows = std::cmp::max(4, highest + 1 - first);
+
+        let mut lines = vec![];
+
+        lines.push(format!(
+            "    {}",
+            self.frets
+                .iter()
+                .map(|f| match f {
+                    None => "x",
+                    Some(0) => "o",
+                    _ => " ",
+                })
+                .collect::<Vec<&str>>()
+                .join(" ")
+                .trim_end()
+        ));
+
+        let width = self.frets.len() * 2 - 1;
+        if first == 1 {
+            lines.push(format!("    {}", "=".repeat(width)));
+        } else {
+            lines.push(format!("    {}", "-".repeat(width)));
+        }
+
+        for fret in first..first + rows {
+            lines.push(format!(
+                "{:>3} {}",
+                if fret == first || first == 1 {
+                    fret.to_string()
+                } else {
+                    String::new()
+                },
+                self.frets
+                    .iter()
+                    .map(|&f| if f == Some(fret) { "*" } else { "|" })
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            ));
+        }
+
+        if self.capo > 0 {
+            lines.push(format!("    capo {}", self.capo));
+        }
+
+        lines.join("\n")
+    }
+}
+
+// Returns sounding midi note of string at fret above capo.
+// Capo does not cover short strings starting above it.
+fn sounding(instrument: &Instrument, capo: u8, string: usize, fret: u8) -> Option<u8> {
+    if fret == 0 && capo <= instrument.first_fret[string] {
+        Some(instrument.strings[string])
+    } else {
+        instrument.pitch(string, capo + fret)
+    }
+}
+
+// Returns fret options for a string, None being muted
+fn string_options(
+    instrument: &Instrument,
+    capo: u8,
+    string: usize,
+    pcs: &[u8],
+    window: (u8, u8),
+) -> Vec<Option<u8>> {
+    let mut options = vec![None];
+
+    let matches = |fret: u8| {
+        sounding(instrument, capo, string, fret).is_some_and(|m| pcs.contains(&(m % 12)))
+    };
+
+    if matches(0) {
+        options.push(Some(0));
+    }
+
+    for fret in window.0..=window.1 {
+        if fret > 0 && matches(fret) {
+            options.push(Some(fret));
+        }
+    }
+
+    options
+}
+
+// Scores shape, lower is better. None when shape is not playable.
+fn score(
+    shape: &[Option<u8>],
+    instrument: &Instrument,
+    capo: u8,
+    root: u8,
+    required: &[u8],
+) -> Option<i32> {
+    let sounding: Vec<(usize, u8)> = shape
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| f.and_then(|f| sounding(instrument, capo, i, f).map(|m| (i, m))))
+        .collect();
+
+    if sounding.len() < std::cmp::min(3, shape.len()) {
+        return None;
+    }
+
+    // required tones
+    let intervals: Vec<u8> = sounding
+        .iter()
+        .map(|&(_, m)| (m + 12 - root) % 12)
+        .collect();
+    if !required.iter().all(|r| intervals.contains(r)) {
+        return None;
+    }
+
+    // fingers needed, barring lowest fret when needed
+    let fretted: Vec<u8> = shape.iter().filter_map(|&f| f).filter(|&f| f > 0).collect();
+    let lowest = fretted.iter().min().copied().unwrap_or(0);
+    let mut barre = false;
+    if fretted.len() > 4 {
+        if 1 + fretted.iter().filter(|&&f| f > lowest).count() > 4 {
+            return None;
+        }
+        barre = true;
+    }
+
+    let first = sounding[0].0;
+    let last = sounding[sounding.len() - 1].0;
+    let inner_mutes = (first..=last).filter(|&i| shape[i].is_none()).count() as i32;
+    // muting top strings thins the melody voice, and reentrant tunings
+    // have no bass strings that would be muted for a root in bass
+    let mut outer_mutes = (shape.len() - 1 - last) as i32;
+    if instrument.reentrant() {
+        outer_mutes += first as i32;
+    }
+    let bass = sounding.iter().map(|&(_, m)| m).min().unwrap();
+
+    let mut result = 0;
+
+    result += lowest as i32;
+    result += fretted.iter().max().copied().unwrap_or(0) as i32 - lowest as i32;
+    result += inner_mutes * 6;
+    result += outer_mutes * 6;
+    // each sounding string outweighs reaching a few frets further
+    result -= sounding.len() as i32 * 4;
+    result -= shape.iter().filter(|&&f| f == Some(0)).count() as i32;
+
+    // reentrant tunings have no real bass string
+    if bass % 12 != root && !instrument.reentrant() {
+        result += 8;
+    }
+    if barre {
+        result += 2;
+    }
+
+    Some(result)
+}
+
+fn search(
+    options: &[Vec<Option<u8>>],
+    shape: &mut Vec<Option<u8>>,
+    found: &mut Vec<Vec<Option<u8>>>,
+) {
+    if shape.len() == options.len() {
+        found.push(shape.clone());
+        return;
+    }
+
+    for &o in options[shape.len()].iter() {
+        shape.push(o);
+        search(options, shape, found);
+        shape.pop();
+    }
+}
+
+/// Returns playable fingerings of chord, best first
+pub fn find_shapes(chord: &Chord, options: &ShapeOptions) -> Vec<Fingering> {
+    let pcs = chord.pitch_classes();
+    let root = util::pitch_class(chord.root());
+    let instrument = &options.instrument;
+    let frets = std::cmp::min(options.frets, instrument.frets.saturating_sub(options.capo));
+
+    let required = options.required.clone().unwrap_or_else(|| {
+        let intervals = chord.intervals();
+        intervals
+            .iter()
+            .filter(|&&i| intervals.len() < 4 || i != 7)
+            .copied()
+            .collect()
+    });
+
+    let mut seen = HashSet::new();
+    let mut fingerings = vec![];
+
+    // search fret windows up the neck
+    let span = std::cmp::max(options.max_span, 1);
+    for start in 1..=frets.saturating_sub(span - 1).max(1) {
+        let window = (start, start + span - 1);
+        let string_options: Vec<Vec<Option<u8>>> = (0..instrument.strings.len())
+            .map(|i| string_options(instrument, options.capo, i, &pcs, window))
+            .collect();
+
+        let mut found = vec![];
+        search(&string_options, &mut vec![], &mut found);
+
+        for shape in found {
+            if !seen.insert(shape.clone()) {
+                continue;
+            }
+
+            if let Some(score) = score(&shape, instrument, options.capo, root, &required) {
+                fingerings.push(Fingering {
+                    chord: chord.name.clone(),
+                    notes: shape
+                        .iter()
+                        .enumerate()
+                        .map(|(i, f)| {
+                            f.and_then(|f| sounding(instrument, options.capo, i, f))
+                                .map(|m| util::note_to_str(chord.spell(m)).to_uppercase())
+                        })
+                        .collect(),
+                    frets: shape,
+                    capo: options.capo,
+                    score,
+                });
+            }
+        }
+    }
+
+    fingerings.sort_by_key(|f| f.score);
+    fingerings
+}
+
+/// Renders scale notes on instrument neck up to given fret
+/// as ascii diagram, highest string on top
+pub fn scale_diagram(instrument: &Instrument, notes: &[String], frets: u8) -> String {
+    let frets = std::cmp::min(frets, instrument.frets);
+    let spelled: Vec<(u8, String)> = notes
+        .iter()
+        .map(|n| (util::pitch_class(util::str_to_note(n)), n.to_uppercase()))
+        .collect();
+    let name = |midi: Option<u8>| {
+        midi.and_then(|m| spelled.iter().find(|(pc, _)| *pc == m % 12))
+            .map(|(_, n)| n.clone())
+    };
+
+    let mut lines = vec![];
+
+    lines.push(format!(
+        "      {}",
+        (1..=frets)
+            .map(|f| match f {
+                3 | 5 | 7 | 9 | 12 | 15 | 17 | 19 | 21 | 24 => format!("{:^3} ", f),
+                _ => "    ".to_string(),
+            })
+            .collect::<String>()
+            .trim_end()
+    ));
+
+    for string in (0..instrument.strings.len()).rev() {
+        let mut line = format!(
+            "{:>3} ||",
+            name(instrument.pitch(string, 0)).unwrap_or_default()
+        );
+
+        for fret in 1..=frets {
+            if fret <= instrument.first_fret[string] {
+                line.push_str("    ");
+            } else {
+                match name(instrument.pitch(string, fret)) {
+                    Some(n) => line.push_str(&format!("{:-^3}|", n)),
+                    None => line.push_str("---|"),
+                }
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes(root: &str, intervals: Vec<u8>, options: &ShapeOptions) -> Vec<String> {
+        let chord = Chord::new(root, intervals, false, 0);
+        find_shapes(&chord, options)
+            .iter()
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn open_chords_in_standard_tuning() {
+        let options = ShapeOptions::default();
+        assert_eq!(shapes("c", vec![0, 4, 7], &options)[0], "x32010");
+        assert_eq!(shapes("g", vec![0, 4, 7], &options)[0], "320003");
+        assert_eq!(shapes("e", vec![0, 3, 7], &options)[0], "022000");
+    }
+
+    #[test]
+    fn alternative_shapes_follow_the_best() {
+        let found = shapes("c", vec![0, 4, 7], &ShapeOptions::default());
+        assert_eq!(found[..2], ["x32010", "x32013"]);
+    }
+
+    #[test]
+    fn frets_are_relative_to_capo() {
+        let options = ShapeOptions {
+            capo: 2,
+            ..ShapeOptions::default()
+        };
+        assert_eq!(shapes("d", vec![0, 4, 7], &options)[0], "x32010 (capo 2)");
+    }
+
+    #[test]
+    fn ukulele_c_uses_every_string() {
+        let options = ShapeOptions {
+            instrument: instrument::get_instrument("ukulele").unwrap(),
+            ..ShapeOptions::default()
+        };
+        assert_eq!(shapes("c", vec![0, 4, 7], &options)[0], "0003");
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use crate::cadence::Cadence;
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::progression::{Progression, ProgressionChord};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Model {
+    Functional,
+    Markov,
+}
+
+impl From<Model> for String {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::Functional => "functional",
+            Model::Markov => "markov",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Model {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "markov" => Model::Markov,
+            _ => Model::Functional,
+        }
+    }
+}
+
+pub fn supported_models() -> Vec<String> {
+    ["functional", "markov"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Options for progression generation
+pub struct GeneratorOptions {
+    pub model: Model,
+    /// Same seed gives the same progression
+    pub seed: u64,
+    /// Minimum number of chords, cadence included
+    pub min_length: usize,
+    /// Maximum number of chords, cadence included
+    pub max_length: usize,
+    /// Closing cadence
+    pub cadence: Cadence,
+    /// Use seventh chords of the scale instead of triads
+    pub sevenths: bool,
+    /// Longest context of the Markov model
+    pub order: usize,
+    /// Roman numeral progressions training the Markov model, one per line
+    pub corpus: Vec<String>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            model: Model::Functional,
+            seed: 0,
+            min_length: 4,
+            max_length: 8,
+            cadence: Cadence::Authentic,
+            sevenths: false,
+            order: 2,
+            corpus: vec![],
+        }
+    }
+}
+
+// Splitmix64 generator, reproducible across platforms
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Index picked with probability relative to its weight
+    fn weighted(&mut self, weights: &[u32]) -> Option<usize> {
+        let total: u32 = weights.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = (self.next() % total as u64) as u32;
+        weights.iter().position(|&w| {
+            if pick < w {
+                true
+            } else {
+                pick -= w;
+                false
+            }
+        })
+    }
+}
+
+// Harmonic functions of scale degrees
+const TONIC: usize = 0;
+const SUBDOMINANT: usize = 1;
+const DOMINANT: usize = 2;
+
+pub(crate) const FUNCTIONS: [usize; 7] = [
+    TONIC,
+    SUBDOMINANT,
+    TONIC,
+    SUBDOMINANT,
+    DOMINANT,
+    TONIC,
+    DOMINANT,
+];
+
+// Weight of degree within its function
+const DEGREE_WEIGHTS: [u32; 7] = [3, 2, 1, 2, 3, 2, 1];
+
+// Weights of moving from function to T, S and D
+pub(crate) const MOVES: [[u32; 3]; 3] = [[1, 3, 2], [1, 1, 3], [4, 0, 1]];
+
+// Tertian chord on each scale degree, as found by analyze
+fn degree_chords(key: &str, scale: &str, sevenths: bool) -> Vec<ProgressionChord> {
+    let notes = crate::scale_notes(key, scale);
+    let (_, chords) = crate::analyze(key, scale, true);
+
+    let tertian = |c: &Chord| {
+        let intervals = c.intervals();
+        let has = |i: u8| intervals.contains(&i);
+        let seventh = has(9) || has(10) || has(11);
+
+        (has(3) || has(4))
+            && (has(6) || has(7) || has(8))
+            && intervals.len() == if sevenths { 4 } else { 3 }
+            && (!sevenths || seventh)
+    };
+
+    notes
+        .iter()
+        .filter_map(|&n| {
+            chords
+                .iter()
+                .find(|c| c.root() == n && tertian(c))
+                .map(|c| ProgressionChord {
+                    numeral: c.styled_name(Style::Roman, &notes),
+                    chord: c.clone(),
+                    bass: None,
+                    beats: 4.0,
+                })
+        })
+        .collect()
+}
+
+// Degrees following functional grammar T -> S -> D -> T from tonic,
+// the last one leading to the given degree
+fn functional(rng: &mut Rng, length: usize, next: usize) -> Vec<usize> {
+    let mut degrees = vec![0];
+
+    while degrees.len() < length {
+        let last = degrees[degrees.len() - 1];
+        let closing = degrees.len() == length - 1;
+        let moves: Vec<u32> = (0..3)
+            .map(|f| {
+                if closing && MOVES[f][FUNCTIONS[next]] == 0 {
+                    0
+                } else {
+                    MOVES[FUNCTIONS[last]][f]
+                }
+            })
+            .collect();
+        let function = rng.weighted(&moves).unwrap_or(SUBDOMINANT);
+
+        let weights: Vec<u32> = (0..7)
+            .map(|d| {
+                if FUNCTIONS[d] == function && d != last && !(closing && d == next) {
+                    DEGREE_WEIGHTS[d]
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        if let Some(d) = rng.weighted(&weights) {
+            degrees.push(d);
+        }
+    }
+
+    degrees
+}
+
+// N-gram counts of numerals following each context, start marked by "^"
+fn train(corpus: &[String], order: usize) -> BTreeMap<Vec<String>, Vec<(String, u32)>> {
+    let mut model: BTreeMap<Vec<String>, Vec<(String, u32)>> = BTreeMap::new();
+
+    for line in corpus {
+        let mut tokens = vec!["^".to_string()];
+        tokens.extend(line.split_whitespace().map(|s| s.to_string()));
+
+        for i in 1..tokens.len() {
+            for n in 1..=order.min(i) {
+                let next = model.entry(tokens[i - n..i].to_vec()).or_default();
+                match next.iter_mut().find(|(t, _)| *t == tokens[i]) {
+                    Some(entry) => entry.1 += 1,
+                    None => next.push((tokens[i].clone(), 1)),
+                }
+            }
+        }
+    }
+
+    model
+}
+
+// Numerals sampled from the longest known context
+fn markov(rng: &mut Rng, corpus: &[String], order: usize, length: usize) -> Vec<String> {
+    let model = train(corpus, order.max(1));
+    let mut tokens = vec!["^".to_string()];
+
+    while tokens.len() <= length {
+        let next = (1..=order.max(1).min(tokens.len()))
+            .rev()
+            .find_map(|n| model.get(&tokens[tokens.len() - n..]))
+            .or_else(|| model.get(&tokens[..1]));
+
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+
+        let weights: Vec<u32> = next.iter().map(|n| n.1).collect();
+        if let Some(i) = rng.weighted(&weights) {
+            tokens.push(next[i].0.clone());
+        }
+    }
+
+    tokens.remove(0);
+    tokens
+}
+
+/// Generates chord progression in key and scale, closing with cadence.
+/// The functional model walks tonic, subdominant and dominant chords
+/// of heptatonic scales, the Markov model follows the corpus.
+pub fn generate(key: &str, scale: &str, options: &GeneratorOptions) -> Progression {
+    let mut rng = Rng(options.seed);
+    let degrees = degree_chords(key, scale, options.sevenths);
+
+    let mut result = Progression {
+        key: key.to_string(),
+        scale: scale.to_string(),
+        chords: vec![],
+    };
+    if degrees.len() != 7 {
+        return result;
+    }
+
+    let cadence = options.cadence.degrees();
+    let min = options.min_length.max(cadence.len() + 1);
+    let max = options.max_length.max(min);
+    let length = min + (rng.next() % (max - min + 1) as u64) as usize;
+    let body = length - cadence.len();
+
+    result.chords = match options.model {
+        Model::Functional => functional(&mut rng, body, cadence[0])
+            .into_iter()
+            .map(|d| degrees[d].clone())
+            .collect(),
+        Model::Markov => markov(&mut rng, &options.corpus, options.order, body)
+            .iter()
+            .filter_map(|numeral| Progression::parse(numeral, key, scale).ok())
+            .flat_map(|p| p.chords)
+            .collect(),
+    };
+
+    // an empty or exhausted corpus is padded with the tonic
+    while result.chords.len() < body {
+        result.chords.push(degrees[0].clone());
+    }
+
+    result
+        .chords
+        .extend(cadence.iter().map(|&d| degrees[d].clone()));
+    result
+}
+
+/// Reads Markov corpus of Roman numeral progressions, one per line,
+/// skipping lines that don't parse
+pub fn corpus(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty() && Progression::parse(l, "c", "major").is_ok())
+        .map(|l| l.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(p: &Progression) -> Vec<String> {
+        p.chords.iter().map(|c| c.chord.name.clone()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_progression() {
+        let options = GeneratorOptions {
+            seed: 42,
+            ..GeneratorOptions::default()
+        };
+        let a = generate("C", "major", &options);
+        assert!(names(&a) == names(&generate("C", "major", &options)));
+        assert!((4..=8).contains(&a.chords.len()));
+    }
+
+    #[test]
+    fn closes_with_cadence() {
+        for seed in 0..8 {
+            let options = GeneratorOptions {
+                seed,
+                ..GeneratorOptions::default()
+            };
+            let p = names(&generate("C", "major", &options));
+            assert_eq!(p[p.len() - 2..], ["G", "C"]);
+        }
+    }
+
+    #[test]
+    fn markov_follows_corpus() {
+        let options = GeneratorOptions {
+            model: Model::Markov,
+            corpus: corpus("I vi IV V\nnot a progression\nI vi IV V"),
+            min_length: 6,
+            max_length: 6,
+            ..GeneratorOptions::default()
+        };
+        assert_eq!(options.corpus.len(), 2);
+        let p = names(&generate("C", "major", &options));
+        assert_eq!(p.len(), 6);
+        assert_eq!(p[..4], ["C", "Am", "F", "G"]);
+    }
+}
@@ -0,0 +1,152 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Stack {
+    Triad,
+    Seventh,
+    Ninth,
+    Eleventh,
+    Thirteenth,
+}
+
+impl From<Stack> for String {
+    fn from(stack: Stack) -> Self {
+        match stack {
+            Stack::Triad => "triad",
+            Stack::Seventh => "seventh",
+            Stack::Ninth => "ninth",
+            Stack::Eleventh => "eleventh",
+            Stack::Thirteenth => "thirteenth",
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for Stack {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match &s.to_lowercase()[..] {
+            "triad" | "triads" | "3" => Ok(Stack::Triad),
+            "seventh" | "sevenths" | "7" => Ok(Stack::Seventh),
+            "ninth" | "ninths" | "9" => Ok(Stack::Ninth),
+            "eleventh" | "elevenths" | "11" => Ok(Stack::Eleventh),
+            "thirteenth" | "thirteenths" | "13" => Ok(Stack::Thirteenth),
+            _ => Err(format!("Unknown harmonization stack: {}", s)),
+        }
+    }
+}
+
+impl Stack {
+    /// Number of stacked thirds, root included
+    pub fn voices(self) -> usize {
+        match self {
+            Stack::Triad => 3,
+            Stack::Seventh => 4,
+            Stack::Ninth => 5,
+            Stack::Eleventh => 6,
+            Stack::Thirteenth => 7,
+        }
+    }
+}
+
+pub fn supported_stacks() -> Vec<String> {
+    ["triad", "seventh", "ninth", "eleventh", "thirteenth"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Harmonization {
+    /// Scale degree, starting from 1
+    pub degree: u8,
+    /// Roman numeral of chord in key
+    pub numeral: String,
+    pub chord: Chord,
+}
+
+impl fmt::Display for Harmonization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<3} {1:<16} {2:}",
+            self.degree, &self.numeral, &self.chord
+        )
+    }
+}
+
+/// Stacks thirds of scale on each degree, in degree order.
+/// Fails for scales other than heptatonic.
+pub fn harmonize(key: &str, scale: &str, stack: Stack) -> Result<Vec<Harmonization>, String> {
+    let notes = crate::scale_notes(key, scale);
+
+    if notes.len() != 7 {
+        return Err(format!("Scale has no seven degrees: {}", scale));
+    }
+
+    Ok((0..7)
+        .map(|degree| {
+            let root = notes[degree];
+            let root_pc = util::pitch_class(root);
+            let intervals: Vec<u8> = (0..stack.voices())
+                .map(|i| (util::pitch_class(notes[(degree + i * 2) % 7]) + 12 - root_pc) % 12)
+                .collect();
+
+            let chord = Chord::from_intervals(root, intervals, &notes);
+
+            Harmonization {
+                degree: degree as u8 + 1,
+                numeral: chord.styled_name(Style::Roman, &notes),
+                chord,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diatonic_sevenths_of_major() {
+        let names: Vec<String> = harmonize("c", "major", Stack::Seventh)
+            .unwrap()
+            .into_iter()
+            .map(|h| h.chord.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["C(M7)", "Dm(7)", "Em(7)", "F(M7)", "G(7)", "Am(7)", "Bdim(7)"]
+        );
+    }
+
+    #[test]
+    fn numerals_of_harmonic_minor() {
+        let numerals: Vec<String> = harmonize("a", "harmonicminor", Stack::Triad)
+            .unwrap()
+            .into_iter()
+            .map(|h| h.numeral)
+            .collect();
+        assert_eq!(numerals, vec!["i", "ii°", "III+", "iv", "V", "VI", "vii°"]);
+    }
+
+    #[test]
+    fn parses_plural_stacks() {
+        assert!(Stack::try_from("sevenths".to_string()) == Ok(Stack::Seventh));
+        assert!(Stack::try_from("Thirteenths".to_string()) == Ok(Stack::Thirteenth));
+        assert!(Stack::try_from("fourths".to_string()).is_err());
+    }
+
+    #[test]
+    fn rejects_scales_without_seven_degrees() {
+        let err = harmonize("c", "pentatonic", Stack::Triad).err().unwrap();
+        assert!(err.contains("pentatonic"));
+    }
+}
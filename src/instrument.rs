@@ -0,0 +1,169 @@
+use std::convert::TryFrom;
+
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+/// Fretted instrument profile.
+/// Strings are listed in physical order, lowest course first,
+/// so that reentrant tunings keep their string order.
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+pub struct Instrument {
+    pub name: String,
+    /// Open string pitches as midi note numbers
+    pub strings: Vec<u8>,
+    /// Number of frets on the neck
+    pub frets: u8,
+    /// Fret each string starts from, nonzero for short strings
+    /// (eg. banjo's fifth string starting at 5th fret)
+    pub first_fret: Vec<u8>,
+}
+
+impl TryFrom<String> for Instrument {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        get_instrument(&s)
+    }
+}
+
+impl Instrument {
+    /// Creates instrument from open string pitches, all starting from nut
+    pub fn new(name: &str, strings: Vec<u8>, frets: u8) -> Instrument {
+        Instrument {
+            name: name.to_string(),
+            first_fret: vec![0; strings.len()],
+            strings,
+            frets,
+        }
+    }
+
+    /// True when string pitches do not ascend in string order
+    /// (eg. ukulele's high G)
+    pub fn reentrant(&self) -> bool {
+        self.strings.windows(2).any(|w| w[1] < w[0])
+    }
+
+    /// Returns sounding midi note of string at fret, None if fret is not playable
+    pub fn pitch(&self, string: usize, fret: u8) -> Option<u8> {
+        let first = self.first_fret[string];
+
+        if fret > self.frets || (fret > 0 && fret <= first) {
+            None
+        } else if fret == 0 {
+            Some(self.strings[string])
+        } else {
+            Some(self.strings[string] + fret - first)
+        }
+    }
+}
+
+pub fn supported_instruments() -> Vec<String> {
+    [
+        "guitar",
+        "dropd",
+        "dadgad",
+        "openg",
+        "7string",
+        "ukulele",
+        "tenorukulele",
+        "baritoneukulele",
+        "bass",
+        "bass5",
+        "bass6",
+        "mandolin",
+        "banjo",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Returns instrument profile by name,
+/// or custom instrument from "E2,A2,..." tuning
+pub fn get_instrument(name: &str) -> Result<Instrument, String> {
+    let (strings, frets) = match name {
+        "guitar" | "standard" => ("E2,A2,D3,G3,B3,E4", 19),
+        "dropd" => ("D2,A2,D3,G3,B3,E4", 19),
+        "dadgad" => ("D2,A2,D3,G3,A3,D4", 19),
+        "openg" => ("D2,G2,D3,G3,B3,D4", 19),
+        "7string" => ("B1,E2,A2,D3,G3,B3,E4", 24),
+        "ukulele" | "sopranoukulele" => ("G4,C4,E4,A4", 12),
+        "tenorukulele" => ("G4,C4,E4,A4", 18),
+        "baritoneukulele" => ("D3,G3,B3,E4", 18),
+        "bass" => ("E1,A1,D2,G2", 20),
+        "bass5" => ("B0,E1,A1,D2,G2", 24),
+        "bass6" => ("B0,E1,A1,D2,G2,C3", 24),
+        "mandolin" => ("G3,D4,A4,E5", 17),
+        "banjo" => ("G4,D3,G3,B3,D4", 22),
+        _ if name.contains(|c: char| c.is_ascii_digit()) => (name, 24),
+        _ => return Err(format!("Unknown instrument: {}", name)),
+    };
+
+    let mut instrument = Instrument::new(name, parse_tuning(strings)?, frets);
+
+    // short fifth string
+    if name == "banjo" {
+        instrument.first_fret[0] = 5;
+    }
+
+    Ok(instrument)
+}
+
+// Parses tuning from "E2,A2,..." format
+fn parse_tuning(tuningstr: &str) -> Result<Vec<u8>, String> {
+    tuningstr
+        .split(',')
+        .map(|s| util::str_to_midi(s.trim()).ok_or(format!("Invalid tuning note: {}", s)))
+        .collect()
+}
+
+pub fn friendly_name(name: &str) -> String {
+    String::from(match name {
+        "guitar" | "standard" => "Guitar",
+        "dropd" => "Guitar (drop D)",
+        "dadgad" => "Guitar (DADGAD)",
+        "openg" => "Guitar (open G)",
+        "7string" => "7-string guitar",
+        "ukulele" | "sopranoukulele" => "Soprano ukulele",
+        "tenorukulele" => "Tenor ukulele",
+        "baritoneukulele" => "Baritone ukulele",
+        "bass" => "Bass",
+        "bass5" => "5-string bass",
+        "bass6" => "6-string bass",
+        "mandolin" => "Mandolin",
+        "banjo" => "5-string banjo",
+        _ => name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_custom_tuning() {
+        let instrument = get_instrument("d2,a2,d3,g3,a3,d4").unwrap();
+        assert_eq!(instrument.strings, vec![38, 45, 50, 55, 57, 62]);
+    }
+
+    #[test]
+    fn ukulele_is_reentrant() {
+        assert!(get_instrument("ukulele").unwrap().reentrant());
+        assert!(!get_instrument("baritoneukulele").unwrap().reentrant());
+    }
+
+    #[test]
+    fn banjo_fifth_string_starts_at_fifth_fret() {
+        let banjo = get_instrument("banjo").unwrap();
+        assert_eq!(banjo.pitch(0, 0), Some(67));
+        assert_eq!(banjo.pitch(0, 3), None);
+        assert_eq!(banjo.pitch(0, 6), Some(68));
+        assert_eq!(banjo.pitch(1, 3), Some(53));
+    }
+
+    #[test]
+    fn rejects_unknown_instrument_and_notes() {
+        assert!(get_instrument("ukelele").is_err());
+        assert!(get_instrument("h2,a2").is_err());
+    }
+}
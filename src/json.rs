@@ -2,16 +2,29 @@ use crate::chord::Chord;
 use serde_json::Value;
 
 pub fn serialize(notes: Vec<String>, chords: Vec<Chord>) -> String {
+    serialize_scale(notes, chords).to_string()
+}
+
+pub fn serialize_directional(
+    ascending: (Vec<String>, Vec<Chord>),
+    descending: (Vec<String>, Vec<Chord>),
+) -> String {
+    let mut value = serialize_scale(ascending.0, ascending.1);
+    value["descending"] = serialize_scale(descending.0, descending.1);
+    value.to_string()
+}
+
+fn serialize_scale(notes: Vec<String>, chords: Vec<Chord>) -> Value {
     json!({
         "notes": json!(notes),
-        "chords": json!(chords.into_iter().map(|c| serialize_chord(c)).collect::<Vec<Value>>()) })
-    .to_string()
+        "chords": json!(chords.into_iter().map(serialize_chord).collect::<Vec<Value>>()) })
 }
 
 fn serialize_chord(chord: Chord) -> Value {
     json!({
         "name": chord.name,
         "notes": json!(&chord.notes.iter().map(|c| c.to_uppercase()).collect::<Vec<String>>()),
-        "extended": chord.extended
+        "extended": chord.extended,
+        "tension": json!(chord.tension)
     })
 }
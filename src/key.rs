@@ -0,0 +1,296 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::{self, Style};
+use crate::progression;
+use crate::scale;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+// Conventional tonic spelling of pitch classes, C first
+pub(crate) const TONICS: [(char, i8); 12] = [
+    ('c', 0),
+    ('d', -1),
+    ('d', 0),
+    ('e', -1),
+    ('e', 0),
+    ('f', 0),
+    ('f', 1),
+    ('g', 0),
+    ('a', -1),
+    ('a', 0),
+    ('b', -1),
+    ('b', 0),
+];
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct KeyChord {
+    pub name: String,
+    /// Roman numeral in key (eg. "ii7" or "V7/V")
+    pub numeral: String,
+    /// Diatonic, secondary, borrowed or chromatic
+    pub fit: String,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct KeyCandidate {
+    /// Tonic note
+    pub key: String,
+    pub scale: String,
+    pub score: i16,
+    pub chords: Vec<KeyChord>,
+}
+
+impl fmt::Display for KeyCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<3} {1:<16} {2:>3}  {3:}",
+            &self.key,
+            scale::friendly_name(&self.scale),
+            self.score,
+            &self
+                .chords
+                .iter()
+                .map(|c| match &c.fit[..] {
+                    "diatonic" => c.numeral.clone(),
+                    fit => format!("{} ({})", c.numeral, fit),
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        )
+    }
+}
+
+// Heptatonic scales of the catalogue, same going up and down
+fn modes() -> Vec<String> {
+    scale::supported_scales()
+        .into_iter()
+        .filter(|s| {
+            !scale::is_directional(s)
+                && scale::get_scale(s).iter().filter(|&&i| i < 12).count() == 7
+        })
+        .collect()
+}
+
+fn pitch_classes(notes: &[(char, i8)]) -> Vec<u8> {
+    notes.iter().map(|&n| util::pitch_class(n)).collect()
+}
+
+fn contains(notes: &[u8], chord: &Chord) -> bool {
+    chord.pitch_classes().iter().all(|pc| notes.contains(pc))
+}
+
+// Dominant of secondary function: major triad, or dominant seventh
+fn is_dominant(intervals: &[u8]) -> bool {
+    intervals.contains(&4) && intervals.contains(&7) && !intervals.contains(&11)
+}
+
+// Leading tone chord of secondary function: diminished triad or seventh
+fn is_leading(intervals: &[u8]) -> bool {
+    intervals.contains(&3) && intervals.contains(&6) && !intervals.contains(&11)
+}
+
+// Secondary function label of chord, tonicizing a diatonic
+// major or minor triad other than tonic (eg. "V7/ii")
+fn secondary(chord: &Chord, key: &[(char, i8)]) -> Option<String> {
+    let root = util::pitch_class(chord.root());
+    let intervals = chord.intervals();
+    let key_pcs = pitch_classes(key);
+
+    (1..7).find_map(|d| {
+        let target = key_pcs[d];
+        let third = (key_pcs[(d + 2) % 7] + 12 - target) % 12;
+        let fifth = (key_pcs[(d + 4) % 7] + 12 - target) % 12;
+        if fifth != 7 {
+            return None;
+        }
+
+        let mut numeral = naming::degree(target, key)?;
+        if third == 3 {
+            numeral = numeral.to_lowercase();
+        }
+
+        if is_dominant(&intervals) && root == (target + 7) % 12 {
+            let seventh = if intervals.contains(&10) { "7" } else { "" };
+            Some(format!("V{}/{}", seventh, numeral))
+        } else if is_leading(&intervals) && root == (target + 11) % 12 {
+            let seventh = match (intervals.contains(&9), intervals.contains(&10)) {
+                (true, _) => "°7",
+                (_, true) => "ø7",
+                _ => "°",
+            };
+            Some(format!("vii{}/{}", seventh, numeral))
+        } else {
+            None
+        }
+    })
+}
+
+// Score of cadential patterns ending on tonic
+fn cadences(chords: &[Chord], tonic: u8) -> i16 {
+    let mut score = 0;
+
+    for pair in chords.windows(2) {
+        let from = util::pitch_class(pair[0].root());
+        let to = util::pitch_class(pair[1].root());
+        let intervals = pair[0].intervals();
+
+        if to == tonic {
+            score += match (from + 12 - tonic) % 12 {
+                // authentic
+                7 if intervals.contains(&4) => 3,
+                // leading tone
+                11 if is_leading(&intervals) => 2,
+                // plagal
+                5 => 1,
+                _ => 0,
+            };
+        }
+    }
+
+    // half cadence
+    if let Some(last) = chords.last() {
+        let root = util::pitch_class(last.root());
+        if root == (tonic + 7) % 12 && last.intervals().contains(&4) {
+            score += 1;
+        }
+    }
+
+    score
+}
+
+/// Analyzes chords in key, flagging chords outside of scale as
+/// secondary functions or borrowed from parallel modes
+pub fn analyze(chords: &[Chord], tonic: (char, i8), scale: &str) -> KeyCandidate {
+    let key = progression::degree_notes(tonic, scale);
+    let key_pcs = pitch_classes(&key);
+    let parallel: Vec<Vec<u8>> = modes()
+        .iter()
+        .filter(|m| m.as_str() != scale)
+        .map(|m| pitch_classes(&progression::degree_notes(tonic, m)))
+        .collect();
+
+    let chords: Vec<KeyChord> = chords
+        .iter()
+        .map(|c| {
+            let roman = c.styled_name(Style::Roman, &key);
+            let (numeral, fit) = if contains(&key_pcs, c) {
+                (roman, "diatonic")
+            } else if let Some(numeral) = secondary(c, &key) {
+                (numeral, "secondary")
+            } else if parallel.iter().any(|p| contains(p, c)) {
+                (roman, "borrowed")
+            } else {
+                (roman, "chromatic")
+            };
+
+            KeyChord {
+                name: c.name.clone(),
+                numeral,
+                fit: fit.to_string(),
+            }
+        })
+        .collect();
+
+    KeyCandidate {
+        key: util::note_to_str(tonic).to_uppercase(),
+        scale: scale.to_string(),
+        score: 0,
+        chords,
+    }
+}
+
+/// Returns keys and modes of the catalogue ranked by likelihood
+/// of chords being in them. Scores come from diatonic chords,
+/// tonic placement and cadences. Ties go to major and minor,
+/// then to the order of the catalogue.
+pub fn detect(chords: &[Chord]) -> Vec<KeyCandidate> {
+    let mut result: Vec<KeyCandidate> = vec![];
+    if chords.is_empty() {
+        return result;
+    }
+
+    for mode in modes() {
+        for (pc, &spelling) in TONICS.iter().enumerate() {
+            let pc = pc as u8;
+            // follow spelling of chords for tonic
+            let tonic = chords
+                .iter()
+                .map(|c| c.root())
+                .find(|&r| util::pitch_class(r) == pc)
+                .unwrap_or(spelling);
+
+            let mut candidate = analyze(chords, tonic, &mode);
+
+            let diatonic = candidate
+                .chords
+                .iter()
+                .filter(|c| c.fit == "diatonic")
+                .count() as i16;
+            let secondary = candidate
+                .chords
+                .iter()
+                .filter(|c| c.fit == "secondary")
+                .count() as i16;
+            let on_tonic = |i: usize| {
+                util::pitch_class(chords[i].root()) == pc && candidate.chords[i].fit == "diatonic"
+            };
+
+            candidate.score = diatonic * 2
+                + secondary
+                + if on_tonic(0) { 2 } else { 0 }
+                + if on_tonic(chords.len() - 1) { 3 } else { 0 }
+                + cadences(chords, pc);
+
+            result.push(candidate);
+        }
+    }
+
+    // stable sort keeps catalogue order within ties
+    result.sort_by_key(|c| (-c.score, c.scale != "major" && c.scale != "minor"));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progression::parse_symbol;
+
+    fn chords(symbols: &str) -> Vec<Chord> {
+        symbols
+            .split_whitespace()
+            .map(|s| parse_symbol(s).unwrap().chord)
+            .collect()
+    }
+
+    #[test]
+    fn detects_major_key_of_ii_v_i() {
+        let best = &detect(&chords("Dm7 G7 C"))[0];
+        assert_eq!((&best.key[..], &best.scale[..]), ("C", "major"));
+    }
+
+    #[test]
+    fn ties_go_to_major_and_minor_only() {
+        let ranked = detect(&chords("C Am Dm"));
+        let rank = |key: &str, scale: &str| {
+            ranked
+                .iter()
+                .position(|c| c.key == key && c.scale == scale)
+                .unwrap()
+        };
+
+        // dorian scores one point over its relative major
+        assert_eq!(ranked[rank("D", "dorian")].score, 9);
+        assert_eq!(ranked[rank("C", "major")].score, 8);
+        assert!(rank("D", "dorian") < rank("C", "major"));
+        assert!(rank("D", "minor") < rank("D", "dorian"));
+    }
+
+    #[test]
+    fn flags_secondary_dominants() {
+        let analysis = analyze(&chords("C A7 Dm G7 C"), ('c', 0), "major");
+        assert_eq!(analysis.chords[1].fit, "secondary");
+        assert_eq!(analysis.chords[1].numeral, "V7/ii");
+    }
+}
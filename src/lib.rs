@@ -2,26 +2,50 @@
 extern crate serde_json;
 
 mod attribute;
+pub mod cadence;
 pub mod chord;
+pub mod chordscale;
 #[cfg(feature = "ffi_c")]
 mod ffi;
+pub mod fretboard;
+pub mod generate;
+pub mod harmonize;
+pub mod instrument;
 mod json;
+pub mod key;
+pub mod melody;
+pub mod modulation;
+pub mod naming;
+pub mod negative;
+pub mod neoriemannian;
 pub mod note;
+pub mod progression;
+pub mod reharmonize;
+pub mod root;
 pub mod scale;
+pub mod scoring;
+pub mod serial;
+pub mod substitution;
+pub mod symmetry;
+pub mod tension;
+pub mod upper;
 pub mod util;
+pub mod voiceleading;
+pub mod voicing;
 
 use self::chord::Chord;
+use self::naming::Style;
+use self::scoring::{ChordScorer, DefaultScorer};
+use self::tension::{Metric, Spectrum};
 #[cfg(feature = "ffi_c")]
 pub use ffi::*;
 use ordered_permutation as op;
 
-// Returns notes in a given key and scale
-fn get_notes(keystr: &str, scalestr: &str) -> Vec<(char, i8)> {
+// Returns notes in a given key and scale intervals
+fn get_notes(keystr: &str, scale: &[u8]) -> Vec<(char, i8)> {
     let key = util::str_to_note(keystr);
     let chromatic_notes: Vec<(char, i8)> = scale::chromatic_notes(key);
 
-    let scale = scale::get_scale(scalestr);
-
     chromatic_notes
         .into_iter()
         .enumerate()
@@ -31,7 +55,12 @@ fn get_notes(keystr: &str, scalestr: &str) -> Vec<(char, i8)> {
 }
 
 //Returns list of chords a given rootnote can create with given list of notes
-fn get_chords(root_note: (char, i8), notes: &Vec<(char, i8)>, extended: bool) -> Vec<Chord> {
+fn get_chords(
+    root_note: (char, i8),
+    notes: &[(char, i8)],
+    extended: bool,
+    scorer: &dyn ChordScorer,
+) -> Vec<Chord> {
     let mut chords = vec![];
 
     //Root note as string presentation
@@ -66,17 +95,17 @@ fn get_chords(root_note: (char, i8), notes: &Vec<(char, i8)>, extended: bool) ->
     // collect indexes in intervals and sort by weight
     permutations.sort_by(|a, b| {
         //collect indexes
-        let aa = util::indexes(&a, &intervals);
-        let bb = util::indexes(&b, &intervals);
+        let aa = util::indexes(a, &intervals);
+        let bb = util::indexes(b, &intervals);
 
-        util::weight_levels(&aa).cmp(&util::weight_levels(&bb))
+        scorer.weight(&aa).cmp(&scorer.weight(&bb))
     });
 
     for mut p in permutations {
         // require 3 notes
         if p.len() > 1 {
-            let weight = util::weight_levels(&util::indexes(&p, &intervals));
-            let is_extended = weight > 4;
+            let weight = scorer.weight(&util::indexes(&p, &intervals));
+            let is_extended = scorer.is_extended(weight);
 
             if extended || !is_extended {
                 // push root note
@@ -97,40 +126,188 @@ fn get_chords(root_note: (char, i8), notes: &Vec<(char, i8)>, extended: bool) ->
     chords
 }
 
+//Returns chord of stacked fourths on root note, if scale has enough fourths,
+//named by its structure in given style
+fn get_quartal_chord(
+    root_note: (char, i8),
+    notes: &[(char, i8)],
+    voices: u8,
+    style: Style,
+) -> Option<Chord> {
+    let root = util::pitch_class(root_note);
+    let pcs: Vec<u8> = notes
+        .iter()
+        .map(|&n| (util::pitch_class(n) + 12 - root) % 12)
+        .collect();
+
+    let mut intervals = vec![0];
+    while intervals.len() < voices as usize {
+        let last = intervals[intervals.len() - 1];
+        // perfect fourth, or augmented when scale has none
+        let next = [5, 6]
+            .iter()
+            .map(|step| (last + step) % 12)
+            .find(|pc| pcs.contains(pc) && !intervals.contains(pc))?;
+        intervals.push(next);
+    }
+
+    let mut chord = Chord::new(&util::note_to_str(root_note), intervals, false, 0);
+    chord.format_notes(notes);
+    if let Some(name) = naming::stacked_name(root_note, &chord.intervals(), style, notes) {
+        chord.name = name;
+        chord.valid = true;
+    }
+    Some(chord)
+}
+
+/// Options for scale analysis
+pub struct Options {
+    /// Include extended chords
+    pub extended: bool,
+    /// Naming style of chords
+    pub style: Style,
+    /// Chord ranking and extended chord split
+    pub scorer: Box<dyn ChordScorer>,
+    /// Harmonic spectrum for chord roughness
+    pub spectrum: Spectrum,
+    /// Order of chords, weight keeping the scorer's order
+    pub sort: Metric,
+    /// Voices of quartal harmonies added on each scale degree, 0 for none
+    pub quartal: u8,
+    /// Use descending form of scales that differ going down
+    pub descending: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            extended: false,
+            style: Style::Default,
+            scorer: Box::new(DefaultScorer),
+            spectrum: Spectrum::default(),
+            sort: Metric::Weight,
+            quartal: 0,
+            descending: false,
+        }
+    }
+}
+
 pub fn analyze(key: &str, scale: &str, extended: bool) -> (Vec<String>, Vec<Chord>) {
-    //Notes in scale
-    let mut notes = get_notes(&key, &scale);
+    analyze_with(
+        key,
+        scale,
+        &Options {
+            extended,
+            ..Default::default()
+        },
+    )
+}
+
+/// Returns notes of scale on key, formatted for readability
+pub fn scale_notes(key: &str, scale: &str) -> Vec<(char, i8)> {
+    let notes = get_notes(key, &scale::get_scale(scale));
 
-    //Format notes for readability
     match scale {
-        "chromatic" => {}
-        _ => notes = util::formatted_notes(notes),
+        "chromatic" => notes,
+        _ => util::formatted_notes(notes),
     }
+}
+
+/// Returns notes of descending form of scale on key, from the bottom up
+/// as for scale_notes
+pub fn descending_scale_notes(key: &str, scale: &str) -> Vec<(char, i8)> {
+    match scale {
+        "chromatic" => scale_notes(key, scale),
+        _ => util::formatted_notes(get_notes(key, &scale::get_descending_scale(scale))),
+    }
+}
+
+/// Analyzes chords of scale on key with options. Notes of descending
+/// form are returned from tonic downwards.
+pub fn analyze_with(key: &str, scale: &str, options: &Options) -> (Vec<String>, Vec<Chord>) {
+    let extended = options.extended;
+
+    //Notes in scale
+    let notes = if options.descending {
+        descending_scale_notes(key, scale)
+    } else {
+        scale_notes(key, scale)
+    };
 
     //Chords in scale
     let mut chords: Vec<Chord> = vec![];
     if scale != "chromatic" {
         for v in &notes {
-            chords.extend(get_chords(*v, &notes, extended));
+            chords.extend(get_chords(*v, &notes, extended, options.scorer.as_ref()));
         }
     }
 
     // deduplicate
-    chords = util::deduplicate(chords);
+    chords = util::deduplicate_with(chords, options.scorer.as_ref());
+
+    // rename after deduplication, as it compares default names
+    if options.style != Style::Default {
+        for chord in chords.iter_mut() {
+            chord.name = chord.styled_name(options.style, &notes);
+        }
+    }
+
+    // quartal harmonies of each degree, replacing tertian chords
+    // of the same notes and keeping their structural names
+    if options.quartal > 2 && scale != "chromatic" {
+        for v in &notes {
+            if let Some(chord) = get_quartal_chord(*v, &notes, options.quartal, options.style) {
+                chords.retain(|c| !c.equals(&chord));
+                chords.push(chord);
+            }
+        }
+    }
+
+    if options.spectrum != Spectrum::default() {
+        for chord in chords.iter_mut() {
+            chord.measure(&options.spectrum);
+        }
+    }
+
+    if options.sort != Metric::Weight {
+        chords.sort_by(|a, b| {
+            a.tension
+                .key(options.sort)
+                .partial_cmp(&b.tension.key(options.sort))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    let mut notes = notes
+        .into_iter()
+        .map(|note| util::note_to_str(note).to_uppercase())
+        .collect::<Vec<String>>();
+    if options.descending {
+        notes[1..].reverse();
+    }
 
     //Return values
-    (
-        notes
-            .into_iter()
-            .map(|note| util::note_to_str(note).to_uppercase())
-            .collect::<Vec<String>>(),
-        chords,
-    )
+    (notes, chords)
 }
 
+/// Analysis as json, with notes and chords of the descending form
+/// for scales that differ going down
 pub fn analyze_json(key: &str, scale: &str, extended: bool) -> String {
     let result = analyze(key, scale, extended);
-    json::serialize(result.0, result.1)
+    if !scale::is_directional(scale) {
+        return json::serialize(result.0, result.1);
+    }
+
+    let descending = analyze_with(
+        key,
+        scale,
+        &Options {
+            extended,
+            descending: true,
+            ..Default::default()
+        },
+    );
+    json::serialize_directional(result, descending)
 }
 
 // Return lib supported scales
@@ -141,3 +318,31 @@ pub fn supported_scales() -> Vec<String> {
 pub fn supported_scales_json() -> String {
     json!({ "scales": json!(scale::supported_scales()) }).to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descending_notes_go_down_from_tonic() {
+        let options = Options {
+            descending: true,
+            ..Default::default()
+        };
+        let (notes, _) = analyze_with("a", "melodicminorclassical", &options);
+        assert_eq!(notes, vec!["A", "G", "F", "E", "D", "C", "B"]);
+    }
+
+    #[test]
+    fn json_of_directional_scale_has_descending_form() {
+        let value: serde_json::Value =
+            serde_json::from_str(&analyze_json("a", "melodicminorclassical", false)).unwrap();
+        assert_eq!(value["notes"][5], "F#");
+        assert_eq!(value["descending"]["notes"][2], "F");
+        assert!(value["descending"]["chords"].is_array());
+
+        let value: serde_json::Value =
+            serde_json::from_str(&analyze_json("c", "major", false)).unwrap();
+        assert!(value.get("descending").is_none());
+    }
+}
@@ -1,10 +1,73 @@
+use chords::cadence::{self, Cadence};
+use chords::chord::Chord;
+use chords::chordscale;
+use chords::fretboard::{self, ShapeOptions};
+use chords::generate::{self, GeneratorOptions, Model};
+use chords::harmonize::{self, Stack};
+use chords::instrument;
+use chords::key;
+use chords::melody::{self, MelodyOptions};
+use chords::modulation;
+use chords::naming::{self, Style};
+use chords::negative::{self, Axis};
+use chords::neoriemannian::{self, Transform};
+use chords::progression::{self, Progression};
+use chords::reharmonize::{self, Strategy};
+use chords::root::{self, RootMethod};
+use chords::scoring::ScoringProfile;
+use chords::serial;
+use chords::substitution;
+use chords::symmetry;
+use chords::tension::Metric;
+use chords::upper;
+use chords::util;
+use chords::voiceleading::{self, VoiceLeadingOptions};
+use chords::voicing::{self, VoicingOptions, VoicingType};
+use std::convert::TryFrom;
 use std::env;
+use std::fs;
+use std::process;
 
 fn main() {
     // defaults
     let mut key = String::from("C");
     let mut scale = String::from("major");
-    let mut extended = false;
+    let mut options = chords::Options::default();
+    let mut voicing: Option<VoicingType> = None;
+    let mut voicing_options = VoicingOptions::default();
+    let mut tension = false;
+    let mut shapes = false;
+    let mut shape_count = 1;
+    let mut neck = false;
+    let mut lead: Option<String> = None;
+    let mut substitute: Option<String> = None;
+    let mut root: Option<String> = None;
+    let mut upper: Option<String> = None;
+    let mut chord_scale: Option<String> = None;
+    let mut stack: Option<Stack> = None;
+    let mut progression: Option<String> = None;
+    let mut detect: Option<String> = None;
+    let mut cadences: Option<String> = None;
+    let mut modulations: Option<String> = None;
+    let mut symmetry = false;
+    let mut row: Option<String> = None;
+    let mut json = false;
+    let mut negative = false;
+    let mut axis: Option<Axis> = None;
+    let mut transform: Option<String> = None;
+    let mut transform_path: Option<String> = None;
+    let mut tonnetz: Option<String> = None;
+    let mut compound = false;
+    let mut melody: Option<String> = None;
+    let mut melody_options = MelodyOptions::default();
+    let mut reharmonize: Option<String> = None;
+    let mut strategy = Strategy::Diatonic;
+    let mut generate = false;
+    let mut generator_options = GeneratorOptions::default();
+    let mut dominant: Option<String> = None;
+    let mut root_method: Option<RootMethod> = None;
+    let mut lead_options = VoiceLeadingOptions::default();
+    let mut shape_options = ShapeOptions::default();
 
     let mut iter = env::args();
 
@@ -22,29 +85,688 @@ fn main() {
             }
 
             "--extended" => {
-                extended = true;
+                options.extended = true;
             }
 
-            "--help" | _ => {
+            "--scoring" => {
+                let path = iter.next().unwrap();
+                let json = fs::read_to_string(&path).expect("Failed to read scoring profile");
+                options.scorer = Box::new(
+                    ScoringProfile::from_json(&json).expect("Failed to parse scoring profile"),
+                );
+            }
+
+            "--sort" => {
+                options.sort = Metric::from(iter.next().unwrap().to_lowercase());
+                tension = true;
+            }
+
+            "--tension" => {
+                tension = true;
+            }
+
+            "--partials" => {
+                options.spectrum.partials = iter.next().unwrap().parse().unwrap_or(6);
+            }
+
+            "--quartal" => {
+                options.quartal = iter.next().unwrap().parse().unwrap_or(3);
+            }
+
+            "--style" => {
+                options.style = Style::from(iter.next().unwrap().to_lowercase());
+            }
+
+            "--voicing" => {
+                voicing = Some(VoicingType::from(iter.next().unwrap().to_lowercase()));
+            }
+
+            "--top" => {
+                voicing_options.top = util::str_to_midi(&iter.next().unwrap());
+            }
+
+            "--shapes" => {
+                shapes = true;
+            }
+
+            "--fretboard" => {
+                neck = true;
+            }
+
+            "--instrument" | "--tuning" => {
+                match instrument::get_instrument(&iter.next().unwrap().to_lowercase()) {
+                    Ok(i) => shape_options.instrument = i,
+                    Err(e) => {
+                        println!("{}", e);
+                        process::exit(1);
+                    }
+                }
+            }
+
+            "--capo" => {
+                shape_options.capo = iter.next().unwrap().parse().unwrap_or(0);
+            }
+
+            "--span" => {
+                shape_options.max_span = iter.next().unwrap().parse().unwrap_or(4);
+            }
+
+            "--fingerings" => {
+                shapes = true;
+                shape_count = iter.next().unwrap().parse().unwrap_or(1);
+            }
+
+            "--lead" => {
+                lead = iter.next();
+            }
+
+            "--generate" => {
+                generate = true;
+                generator_options.model = Model::from(iter.next().unwrap().to_lowercase());
+            }
+
+            "--seed" => {
+                generator_options.seed = iter.next().unwrap().parse().unwrap_or(0);
+            }
+
+            "--length" => {
+                let length = iter.next().unwrap();
+                let mut split = length.split(':').map(|s| s.parse::<usize>());
+                if let Some(Ok(min)) = split.next() {
+                    generator_options.min_length = min;
+                    generator_options.max_length = match split.next() {
+                        Some(Ok(max)) => max,
+                        _ => min,
+                    };
+                }
+            }
+
+            "--cadence" => {
+                generator_options.cadence = Cadence::from(iter.next().unwrap().to_lowercase());
+            }
+
+            "--sevenths" => {
+                generator_options.sevenths = true;
+            }
+
+            "--corpus" => {
+                let path = iter.next().unwrap();
+                let text = fs::read_to_string(&path).expect("Failed to read corpus");
+                generator_options.corpus = generate::corpus(&text);
+            }
+
+            "--order" => {
+                generator_options.order = iter.next().unwrap().parse().unwrap_or(2);
+            }
+
+            "--symmetry" => {
+                symmetry = true;
+            }
+
+            "--row" => {
+                row = iter.next();
+            }
+
+            "--json" => {
+                json = true;
+            }
+
+            "--negative" => {
+                negative = true;
+            }
+
+            "--axis" => match Axis::parse(&iter.next().unwrap()) {
+                Ok(a) => axis = Some(a),
+                Err(e) => {
+                    println!("{}", e);
+                    process::exit(1);
+                }
+            },
+
+            "--transform" => {
+                transform = iter.next();
+            }
+
+            "--path" => {
+                transform_path = iter.next();
+            }
+
+            "--compound" => {
+                compound = true;
+            }
+
+            "--tonnetz" => {
+                tonnetz = iter.next();
+            }
+
+            "--melody" => {
+                melody = iter.next();
+            }
+
+            "--meter" => {
+                if let Some(meter) = melody::parse_meter(&iter.next().unwrap()) {
+                    melody_options.meter = meter;
+                }
+            }
+
+            "--per" => {
+                melody_options.per_bar = iter.next().unwrap().to_lowercase() != "beat";
+            }
+
+            "--reharmonize" => {
+                reharmonize = iter.next();
+            }
+
+            "--strategy" => {
+                strategy = Strategy::from(iter.next().unwrap().to_lowercase());
+            }
+
+            "--modulation" => {
+                modulations = iter.next();
+            }
+
+            "--cadences" => {
+                cadences = iter.next();
+            }
+
+            "--detect" => {
+                detect = iter.next();
+            }
+
+            "--progression" => {
+                progression = iter.next();
+            }
+
+            "--harmonize" => match Stack::try_from(iter.next().unwrap()) {
+                Ok(s) => stack = Some(s),
+                Err(e) => {
+                    println!("{}", e);
+                    process::exit(1);
+                }
+            },
+
+            "--chordscale" => {
+                chord_scale = iter.next();
+            }
+
+            "--upper" => {
+                upper = iter.next();
+            }
+
+            "--dominant" => {
+                dominant = iter.next();
+            }
+
+            "--root" => {
+                root = iter.next();
+            }
+
+            "--method" => {
+                root_method = Some(RootMethod::from(iter.next().unwrap().to_lowercase()));
+            }
+
+            "--substitute" => {
+                substitute = iter.next();
+            }
+
+            "--leap" => {
+                lead_options.max_leap = iter.next().unwrap().parse().ok();
+            }
+
+            "--crossing" => {
+                lead_options.no_crossing = false;
+            }
+
+            "--range" => {
+                let range = iter.next().unwrap();
+                let mut split = range.split(':').map(util::str_to_midi);
+                if let (Some(Some(low)), Some(Some(high))) = (split.next(), split.next()) {
+                    voicing_options.low = low;
+                    voicing_options.high = high;
+                }
+            }
+
+            _ => {
                 print_help();
                 return;
             }
         }
     }
 
-    // Run analysis
-    let (notes, chords) = chords::analyze(&key, &scale, extended);
+    lead_options.range = voicing_options;
 
-    //Print results
-    println!(
-        "Notes in {} {} scale:",
-        &key.to_uppercase(),
-        &chords::scale::friendly_name(&scale)
-    );
-    println!("{}\n", notes.join(" "));
-    println!("Chords found:");
-    for c in chords {
-        println!("{}", c);
+    // Voice lead chords of scale
+    if let Some(lead) = lead {
+        let (_, all) = chords::analyze(&key, &scale, true);
+        // chords of scale keep their spelling in key, others are parsed as symbols
+        let parsed: Result<Vec<Chord>, String> = lead
+            .split_whitespace()
+            .map(|name| match all.iter().find(|c| c.name == name) {
+                Some(c) => Ok(c.clone()),
+                None => progression::parse_symbol(name).map(|c| c.chord),
+            })
+            .collect();
+
+        match parsed {
+            Ok(progression) => println!(
+                "{}",
+                voiceleading::table(&voiceleading::lead(&progression, &lead_options))
+            ),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Upper structures of chord in scale
+    if let Some(name) = upper {
+        let (_, all) = chords::analyze(&key, &scale, true);
+        if let Some(chord) = all.iter().find(|c| c.name == name) {
+            println!("Upper structures of {}:", chord);
+            for u in upper::decompose(chord) {
+                println!("{}", u);
+            }
+        } else {
+            println!("Unknown chord: {}", name);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // Upper structure triads of dominant chord
+    if let Some(root) = dominant {
+        let root = util::str_to_note(&root);
+        println!(
+            "Upper structures of {}7:",
+            util::note_to_str(root).to_uppercase()
+        );
+        for u in upper::dominant_options(root) {
+            println!("{}", u);
+        }
+        return;
+    }
+
+    // Root of given notes
+    if let Some(notes) = root {
+        let notes: Vec<(char, i8)> = notes
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(util::str_to_note)
+            .collect();
+        let methods = match root_method {
+            Some(m) => vec![m],
+            None => vec![
+                RootMethod::Hindemith,
+                RootMethod::Parncutt,
+                RootMethod::Terhardt,
+            ],
+        };
+
+        println!("Root candidates:");
+        for m in methods {
+            println!("{}", root::find_root(&notes, m));
+        }
+        return;
+    }
+
+    // Substitutions for chord of scale
+    if let Some(name) = substitute {
+        let (_, all) = chords::analyze(&key, &scale, true);
+        if let Some(chord) = all.iter().find(|c| c.name == name) {
+            println!("Substitutions for {}:", chord);
+            for s in substitution::substitutions(chord, &key, &scale) {
+                println!("{}", s);
+            }
+        } else {
+            println!("Unknown chord: {}", name);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // Generated progression in key
+    if generate {
+        let p = generate::generate(&key, &scale, &generator_options);
+        println!(
+            "Generated progression in {} {}:",
+            &key.to_uppercase(),
+            &chords::scale::friendly_name(&scale)
+        );
+        println!("{}", p.symbols(Style::Default).join(" "));
+        print!("{}", p);
+        return;
+    }
+
+    // Transpositional symmetry of scale
+    if symmetry {
+        println!("{}", symmetry::analyze_symmetry(&scale));
+        return;
+    }
+
+    // Twelve-tone matrix of row
+    if let Some(input) = row {
+        match serial::parse_row(&input).and_then(|r| serial::matrix(&r)) {
+            Ok(m) if json => println!("{}", m.to_json()),
+            Ok(m) => println!("{}", m),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Neo-Riemannian transforms of triad (eg. "C PLR")
+    if let Some(input) = transform {
+        let mut tokens = input.split_whitespace();
+        let parsed = progression::parse_symbol(tokens.next().unwrap_or_default()).and_then(|s| {
+            tokens
+                .flat_map(|t| t.chars())
+                .map(|c| Transform::try_from(c.to_string()))
+                .collect::<Result<Vec<Transform>, String>>()
+                .map(|t| (s, t))
+        });
+        match parsed {
+            Ok((start, transforms)) => {
+                println!("{}", start.chord);
+                for step in neoriemannian::apply_all(&start.chord, &transforms) {
+                    println!("{}", step);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Shortest transform path between triads
+    if let Some(input) = transform_path {
+        let parsed: Result<Vec<Chord>, String> = input
+            .split_whitespace()
+            .map(|s| progression::parse_symbol(s).map(|c| c.chord))
+            .collect();
+        match parsed {
+            Ok(triads) if triads.len() == 2 => {
+                match neoriemannian::path(&triads[0], &triads[1], compound) {
+                    Some(steps) => {
+                        println!("{}", triads[0]);
+                        for step in steps {
+                            println!("{}", step);
+                        }
+                    }
+                    None => println!("Path is only found between major and minor triads"),
+                }
+            }
+            Ok(_) => println!("Path needs two chords"),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Tonnetz coordinates of triads
+    if let Some(input) = tonnetz {
+        for symbol in input.split_whitespace() {
+            match progression::parse_symbol(symbol) {
+                Ok(c) => match neoriemannian::tonnetz(&c.chord) {
+                    Some(p) => println!("{}", p),
+                    None => println!("{} is not a major or minor triad", symbol),
+                },
+                Err(e) => println!("{}", e),
+            }
+        }
+        return;
+    }
+
+    // Chords for melody in key
+    if let Some(input) = melody {
+        match reharmonize::parse_melody(&input) {
+            Ok(notes) => {
+                let h = melody::harmonize_melody(&notes, &key, &scale, &melody_options);
+                println!(
+                    "Harmonization of melody in {} {} ({}/{}):",
+                    &key.to_uppercase(),
+                    &chords::scale::friendly_name(&scale),
+                    melody_options.meter.0,
+                    melody_options.meter.1
+                );
+                println!("{}", h.progression.symbols(options.style).join(" "));
+                print!("{}", h);
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Reharmonization of numerals, melody or chord symbols
+    if let Some(input) = reharmonize {
+        let melody = match Progression::parse(&input, &key, &scale) {
+            Ok(p) => Ok(reharmonize::melody_of(&p)),
+            Err(_) => reharmonize::parse_melody(&input).or_else(|_| {
+                Progression::from_symbols(&input, &key, &scale).map(|p| reharmonize::melody_of(&p))
+            }),
+        };
+        let result = melody.and_then(|m| {
+            reharmonize::reharmonize(&m, &key, &scale, strategy, &lead_options).map(|r| (m, r))
+        });
+        match result {
+            Ok((melody, alternatives)) => {
+                println!(
+                    "Reharmonizations of {}:",
+                    melody
+                        .iter()
+                        .map(|m| m.note.to_uppercase())
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                );
+                if alternatives.is_empty() {
+                    println!("No reharmonization found");
+                }
+                for r in alternatives {
+                    println!("{}", r);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Key areas of numerals or chord symbols
+    if let Some(input) = modulations {
+        let parsed = Progression::parse(&input, &key, &scale)
+            .or_else(|_| Progression::from_symbols(&input, &key, &scale));
+        match parsed {
+            Ok(p) => {
+                let chords: Vec<Chord> = p.chords.into_iter().map(|c| c.chord).collect();
+                print!("{}", modulation::detect(&chords));
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Cadences of numerals or chord symbols in key
+    if let Some(input) = cadences {
+        let parsed = Progression::parse(&input, &key, &scale)
+            .or_else(|_| Progression::from_symbols(&input, &key, &scale));
+        match parsed {
+            Ok(p) => {
+                println!("Cadences:");
+                for c in cadence::detect(&p) {
+                    println!("{}", c);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Keys of chord symbols
+    if let Some(input) = detect {
+        let parsed: Result<Vec<Chord>, String> = input
+            .split_whitespace()
+            .map(|s| progression::parse_symbol(s).map(|c| c.chord))
+            .collect();
+        match parsed {
+            Ok(chords) => {
+                println!("Likely keys:");
+                for k in key::detect(&chords).iter().take(5) {
+                    println!("{}", k);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Progression of numerals in key
+    if let Some(input) = progression {
+        match Progression::parse(&input, &key, &scale) {
+            Ok(mut p) => {
+                if negative {
+                    p = negative::negative_progression(&p, axis.unwrap_or(Axis::new(&key)));
+                }
+                println!(
+                    "Progression in {} {} ({} beats):",
+                    &key.to_uppercase(),
+                    &chords::scale::friendly_name(&scale),
+                    p.beats()
+                );
+                if options.style != Style::Default {
+                    let notes = p.key_notes();
+                    // styled symbols carry the bass, as slash or figures
+                    for c in p.chords.iter_mut() {
+                        c.chord.name = c.symbol(options.style, &notes);
+                        c.bass = None;
+                    }
+                }
+                print!("{}", p);
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Negative counterparts of chords in scale
+    if negative {
+        let axis = axis.unwrap_or(Axis::new(&key));
+        match negative::negative_chords(&key, &scale, axis) {
+            Ok(negatives) => {
+                println!(
+                    "Negative harmony of {} {} around {}:{} axis:",
+                    &key.to_uppercase(),
+                    &chords::scale::friendly_name(&scale),
+                    util::note_to_str(axis.low).to_uppercase(),
+                    util::note_to_str(axis.high).to_uppercase()
+                );
+                for c in negatives {
+                    println!("{}", c);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Stacked thirds on each degree
+    if let Some(stack) = stack {
+        match harmonize::harmonize(&key, &scale, stack) {
+            Ok(harmonization) => {
+                println!(
+                    "Harmonization of {} {} scale:",
+                    &key.to_uppercase(),
+                    &chords::scale::friendly_name(&scale)
+                );
+                for mut h in harmonization {
+                    if options.style != Style::Default {
+                        let notes: Vec<(char, i8)> = chords::scale_notes(&key, &scale);
+                        h.chord.name = h.chord.styled_name(options.style, &notes);
+                    }
+                    println!("{}", h);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // Chord scales of chord in scale
+    if let Some(name) = chord_scale {
+        let (_, all) = chords::analyze(&key, &scale, true);
+        if let Some(chord) = all.iter().find(|c| c.name == name) {
+            println!("Chord scales for {}:", chord);
+            for s in chordscale::chord_scales_in(chord, &key, &scale) {
+                println!("{}", s);
+            }
+        } else {
+            println!("Unknown chord: {}", name);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if json {
+        println!("{}", chords::analyze_json(&key, &scale, options.extended));
+        return;
+    }
+
+    // Scales with other notes going down are analyzed both ways
+    let directions: &[bool] = if chords::scale::is_directional(&scale) {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    for &descending in directions {
+        // Run analysis
+        options.descending = descending;
+        let (notes, chords) = chords::analyze_with(&key, &scale, &options);
+
+        //Print results
+        println!(
+            "Notes in {} {} scale{}:",
+            &key.to_uppercase(),
+            &chords::scale::friendly_name(&scale),
+            match (directions.len(), descending) {
+                (1, _) => "",
+                (_, false) => ", ascending",
+                (_, true) => ", descending",
+            }
+        );
+        println!("{}\n", notes.join(" "));
+
+        if neck {
+            println!(
+                "{}:",
+                instrument::friendly_name(&shape_options.instrument.name)
+            );
+            println!(
+                "{}\n",
+                fretboard::scale_diagram(&shape_options.instrument, &notes, 12)
+            );
+        }
+
+        println!("Chords found:");
+        for c in chords {
+            println!("{}", c);
+
+            if tension {
+                println!("    {}", c.tension);
+            }
+
+            if let Some(kind) = voicing {
+                if let Some(v) = voicing::voice(&c, kind, &voicing_options) {
+                    println!("    {}", v);
+                }
+            }
+
+            if shapes {
+                for f in fretboard::find_shapes(&c, &shape_options)
+                    .iter()
+                    .take(shape_count)
+                {
+                    println!("    {}\n{}\n", f, f.diagram());
+                }
+            }
+        }
+
+        if !descending && directions.len() > 1 {
+            println!();
+        }
     }
 }
 
@@ -56,6 +778,97 @@ fn print_help() {
     println!("\t--key       Root key");
     println!("\t--scale     Scale for notes");
     println!("\t--extended  Print extended chords (marked with *)");
+    println!("\t--style     Chord naming style");
+    println!("\t--quartal   Add quartal harmonies of given voices on each degree");
+    println!("\t--scoring   Json file of chord scoring profile");
+    println!("\t--tension   Print tension metrics of each chord");
+    println!("\t--sort      Sort chords by weight, dissonance, roughness or harmonicity");
+    println!("\t--partials  Number of harmonic partials for roughness");
+    println!("\t--voicing   Print voicing of each chord");
+    println!("\t--top       Top note target for voicing (eg. E5)");
+    println!("\t--range     Range for voicing (eg. C3:C6)");
+    println!("\t--lead      Print voice leading of chords in scale (eg. \"Dm(7) G(7) C(M7)\")");
+    println!(
+        "\t--chordscale Print scales matching chord in scale, with avoid notes (eg. \"G(7)\")"
+    );
+    println!("\t--upper     Print upper structure triads of chord in scale (eg. \"F(M7)\")");
+    println!("\t--dominant  Print upper structure triads for dominant 7th of root");
+    println!("\t--root      Print root of given notes, bass first (eg. \"E G C\")");
+    println!("\t--method    Root finding method");
+    println!("\t--substitute Print substitutions for chord in scale (eg. \"G(7)\")");
+    println!("\t--symmetry  Print transpositional symmetry of scale");
+    println!(
+        "\t--row       Print twelve-tone matrix of row (eg. \"C B G A♭ ...\" or \"0 e 7 8 ...\")"
+    );
+    println!("\t--json      Print scale analysis or twelve-tone matrix as json");
+    println!("\t--negative  Print negative harmony of chords in scale, or of progression");
+    println!("\t--axis      Axis notes for negative harmony (eg. C:G)");
+    println!("\t--transform Apply neo-Riemannian transforms to triad (eg. \"C PLR\")");
+    println!("\t--path      Print shortest transform path between triads (eg. \"C F#m\")");
+    println!("\t--compound  Use N, S and H transforms in path");
+    println!("\t--tonnetz   Print Tonnetz coordinates of triads");
+    println!("\t--melody    Print chords for melody in key (eg. \"E D C D E:2\")");
+    println!("\t--meter     Time signature of melody (eg. 3/4)");
+    println!("\t--per       Chord for every beat or bar of melody");
+    println!("\t--reharmonize Print reharmonizations of numerals, melody (eg. \"E5:2 D5 C5:1\") or chord symbols");
+    println!("\t--strategy  Reharmonization strategy");
+    println!("\t--leap      Maximum leap of a voice in semitones");
+    println!("\t--crossing  Allow voice crossing");
+    println!("\t--shapes    Print fretted chord shape of each chord");
+    println!("\t--fretboard Print scale notes on instrument neck");
+    println!("\t--instrument Instrument for chord shapes and fretboard");
+    println!("\t--tuning    Custom tuning for chord shapes (eg. D2,A2,D3,G3,A3,D4)");
+    println!("\t--capo      Capo position for chord shapes");
+    println!("\t--span      Maximum fret span for chord shapes");
+    println!("\t--fingerings Number of chord shapes printed for each chord, best first");
+    println!("\t--harmonize Print chords of stacked thirds on each degree of scale");
+    println!("\t--progression Print progression of numerals in key (eg. \"ii7 V7 Imaj7:2\")");
+    println!("\t--detect    Print likely keys of chord symbols (eg. \"Dm7 G7 C\")");
+    println!("\t--generate  Generate progression in key with given model");
+    println!("\t--seed      Seed of generated progression");
+    println!("\t--length    Number of generated chords (eg. 4:8)");
+    println!("\t--cadence   Closing cadence of generated progression");
+    println!("\t--sevenths  Generate seventh chords");
+    println!("\t--corpus    File of numeral progressions for Markov model, one per line");
+    println!("\t--order     Longest context of Markov model");
+    println!("\t--cadences  Print cadences of numerals or chord symbols in key");
+    println!("\t--modulation Print key areas and modulations of numerals or chord symbols");
     println!("\t--help      Prints help");
     chords::scale::print_supported_scales();
+    println!("Supported naming styles:");
+    for s in naming::supported_styles() {
+        println!("\t{}", s);
+    }
+    println!("Supported harmonization stacks:");
+    for s in harmonize::supported_stacks() {
+        println!("\t{}", s);
+    }
+    println!("Supported progression models:");
+    for m in generate::supported_models() {
+        println!("\t{}", m);
+    }
+    println!("Supported neo-Riemannian transforms:");
+    for t in neoriemannian::supported_transforms() {
+        println!("\t{}", t);
+    }
+    println!("Supported reharmonization strategies:");
+    for s in reharmonize::supported_strategies() {
+        println!("\t{}", s);
+    }
+    println!("Supported cadences:");
+    for c in cadence::supported_cadences() {
+        println!("\t{}", c);
+    }
+    println!("Supported root finding methods:");
+    for m in root::supported_methods() {
+        println!("\t{}", m);
+    }
+    println!("Supported voicings:");
+    for v in voicing::supported_voicings() {
+        println!("\t{}", v);
+    }
+    println!("Supported instruments:");
+    for t in instrument::supported_instruments() {
+        println!("\t{}", t);
+    }
 }
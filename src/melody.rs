@@ -0,0 +1,322 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::generate::{FUNCTIONS, MOVES};
+use crate::naming::Style;
+use crate::progression::{Progression, ProgressionChord};
+use crate::reharmonize::MelodyNote;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+// Score of melody fully covered by chord, of the note on the beat
+// being a chord tone, of each tone beyond a triad and of starting
+// and ending on tonic
+const COVERAGE: f32 = 20.0;
+const STRONG: i32 = 4;
+const ADDED: i32 = -3;
+const TONIC: i32 = 3;
+// Score of holding chord, as functional moves go between chords
+const HOLD: i32 = 2;
+// Chords suggested for each segment
+const SUGGESTIONS: usize = 3;
+
+/// Options for melody harmonization
+pub struct MelodyOptions {
+    /// Time signature as beats per bar and beat unit (eg. 3/4).
+    /// Melody lengths count quarter notes, so a 6/8 bar lasts three.
+    pub meter: (u8, u8),
+    /// Suggest chord for every bar instead of every beat
+    pub per_bar: bool,
+}
+
+impl Default for MelodyOptions {
+    fn default() -> Self {
+        MelodyOptions {
+            meter: (4, 4),
+            per_bar: true,
+        }
+    }
+}
+
+/// Parses time signature (eg. "3/4" or "6/8")
+pub fn parse_meter(input: &str) -> Option<(u8, u8)> {
+    let (beats, unit) = input.split_once('/')?;
+    let meter = (beats.parse().ok()?, unit.parse().ok()?);
+    if meter.0 == 0 || meter.1 == 0 {
+        return None;
+    }
+    Some(meter)
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct MelodySegment {
+    /// Beat of segment start in beats of the meter, first beat being 1
+    pub beat: f32,
+    /// Melody notes sounding in segment
+    pub melody: Vec<String>,
+    /// Chord picked for segment
+    pub chord: String,
+    /// Chords ranked by how well they cover the melody
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for MelodySegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:>5}  {1:<12} {2:<10} {3:}",
+            self.beat,
+            &self
+                .melody
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(" "),
+            &self.chord,
+            &self.suggestions.join(" ")
+        )
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct MelodyHarmonization {
+    pub segments: Vec<MelodySegment>,
+    pub progression: Progression,
+}
+
+impl fmt::Display for MelodyHarmonization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.segments {
+            writeln!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+// Melody notes of segment with beats they sound in it, first one
+// sounding at its start
+fn segment(melody: &[MelodyNote], start: f32, end: f32) -> Vec<(u8, &str, f32)> {
+    let mut onset = 0.0;
+    let mut result = vec![];
+
+    for m in melody {
+        let overlap = (onset + m.beats).min(end) - onset.max(start);
+        if overlap > 0.0 {
+            result.push((
+                util::pitch_class(util::str_to_note(&m.note)),
+                &m.note[..],
+                overlap,
+            ));
+        }
+        onset += m.beats;
+    }
+
+    result
+}
+
+// Chords of triads and sevenths with added tones, no omissions
+fn complete(chord: &Chord) -> bool {
+    let intervals = chord.intervals();
+    let has = |i: u8| intervals.contains(&i);
+    (has(3) || has(4)) && (has(6) || has(7) || has(8))
+}
+
+// Share of melody covered by chord, with the note on the beat
+// counting extra, less tones beyond a triad
+fn fit(chord: &Chord, notes: &[(u8, &str, f32)]) -> i32 {
+    let total: f32 = notes.iter().map(|n| n.2).sum();
+    if total == 0.0 {
+        return 0;
+    }
+
+    let pcs = chord.pitch_classes();
+    let covered: f32 = notes
+        .iter()
+        .filter(|n| pcs.contains(&n.0))
+        .map(|n| n.2)
+        .sum();
+    let strong = if notes.first().is_some_and(|n| pcs.contains(&n.0)) {
+        STRONG
+    } else {
+        0
+    };
+    let added = (pcs.len() as i32 - 3).max(0);
+
+    (COVERAGE * covered / total).round() as i32 + strong + ADDED * added
+}
+
+/// Suggests chords of key and scale, as found by analyze, for each beat
+/// or bar of melody. Chords are ranked by how much of the melody they
+/// cover, and picked following tonic, subdominant and dominant function
+/// of heptatonic scale degrees, starting and ending on tonic when fit.
+pub fn harmonize_melody(
+    melody: &[MelodyNote],
+    key: &str,
+    scale: &str,
+    options: &MelodyOptions,
+) -> MelodyHarmonization {
+    let notes = crate::scale_notes(key, scale);
+    let chords: Vec<Chord> = crate::analyze(key, scale, false)
+        .1
+        .into_iter()
+        .filter(complete)
+        .collect();
+
+    let mut result = MelodyHarmonization {
+        segments: vec![],
+        progression: Progression {
+            key: key.to_string(),
+            scale: scale.to_string(),
+            chords: vec![],
+        },
+    };
+
+    let total: f32 = melody.iter().map(|m| m.beats).sum();
+    if chords.is_empty() || total <= 0.0 {
+        return result;
+    }
+
+    // beat of the meter in quarter notes
+    let beat = 4.0 / options.meter.1 as f32;
+    let length = if options.per_bar {
+        options.meter.0 as f32 * beat
+    } else {
+        beat
+    };
+    let count = (total / length).ceil() as usize;
+
+    let degrees: Vec<Option<usize>> = chords
+        .iter()
+        .map(|c| notes.iter().position(|&n| n == c.root()))
+        .collect();
+    let function = |c: usize| {
+        degrees[c]
+            .filter(|_| notes.len() == 7)
+            .map(|d| FUNCTIONS[d])
+    };
+    let tonic = |c: usize| degrees[c] == Some(0);
+
+    let segments: Vec<Vec<(u8, &str, f32)>> = (0..count)
+        .map(|i| segment(melody, i as f32 * length, (i + 1) as f32 * length))
+        .collect();
+    let fits: Vec<Vec<i32>> = segments
+        .iter()
+        .map(|s| chords.iter().map(|c| fit(c, s)).collect())
+        .collect();
+
+    // best scoring path of chords, moves weighted by function
+    let mut scores: Vec<i32> = (0..chords.len())
+        .map(|c| fits[0][c] + if tonic(c) { TONIC } else { 0 })
+        .collect();
+    let mut back: Vec<Vec<usize>> = vec![];
+
+    for (i, fit) in fits.iter().enumerate().skip(1) {
+        let mut next = vec![];
+        let mut from = vec![];
+
+        for (c, fit) in fit.iter().enumerate() {
+            let (prev, score) = (0..chords.len())
+                .map(|p| {
+                    let flow = match (function(p), function(c)) {
+                        _ if p == c => HOLD,
+                        (Some(a), Some(b)) => MOVES[a][b] as i32,
+                        _ => 0,
+                    };
+                    (p, scores[p] + flow)
+                })
+                .fold((0, i32::MIN), |best, s| if s.1 > best.1 { s } else { best });
+
+            let ending = if i == count - 1 && tonic(c) { TONIC } else { 0 };
+            next.push(score + fit + ending);
+            from.push(prev);
+        }
+
+        scores = next;
+        back.push(from);
+    }
+
+    let best = (0..chords.len()).fold(0, |best, c| if scores[c] > scores[best] { c } else { best });
+    let mut path = vec![best];
+    for from in back.iter().rev() {
+        path.push(from[path[path.len() - 1]]);
+    }
+    path.reverse();
+
+    for (i, &c) in path.iter().enumerate() {
+        let mut ranked: Vec<usize> = (0..chords.len()).collect();
+        // stable sort keeps order of analyze on ties
+        ranked.sort_by_key(|&r| -fits[i][r]);
+
+        result.segments.push(MelodySegment {
+            beat: i as f32 * length / beat + 1.0,
+            melody: segments[i].iter().map(|n| n.1.to_string()).collect(),
+            chord: chords[c].name.clone(),
+            suggestions: ranked
+                .iter()
+                .take(SUGGESTIONS)
+                .map(|&r| chords[r].name.clone())
+                .collect(),
+        });
+
+        let beats = (total - i as f32 * length).min(length);
+        result.progression.chords.push(ProgressionChord {
+            numeral: chords[c].styled_name(Style::Roman, &notes),
+            chord: chords[c].clone(),
+            bass: None,
+            beats,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reharmonize;
+
+    fn melody() -> Vec<MelodyNote> {
+        reharmonize::parse_melody("E G C:2 F A D:2 B D G:2 C:4").unwrap()
+    }
+
+    #[test]
+    fn chord_per_bar_follows_melody() {
+        let h = harmonize_melody(&melody(), "C", "major", &MelodyOptions::default());
+        let chords: Vec<&str> = h.segments.iter().map(|s| &s.chord[..]).collect();
+        let beats: Vec<f32> = h.segments.iter().map(|s| s.beat).collect();
+
+        assert_eq!(chords, vec!["C", "Dm", "G", "C"]);
+        assert_eq!(beats, vec![1.0, 5.0, 9.0, 13.0]);
+        assert_eq!(h.segments[0].melody, vec!["e", "g", "c"]);
+    }
+
+    #[test]
+    fn chord_per_beat() {
+        let options = MelodyOptions {
+            per_bar: false,
+            ..MelodyOptions::default()
+        };
+        let h = harmonize_melody(&melody(), "C", "major", &options);
+        assert_eq!(h.segments.len(), 16);
+    }
+
+    #[test]
+    fn parses_meter() {
+        assert_eq!(parse_meter("6/8"), Some((6, 8)));
+        assert_eq!(parse_meter("0/4"), None);
+        assert_eq!(parse_meter("3"), None);
+    }
+
+    #[test]
+    fn compound_meter_bars_last_three_quarters() {
+        let melody = reharmonize::parse_melody("E D C D E E E:2").unwrap();
+        let options = MelodyOptions {
+            meter: (6, 8),
+            per_bar: true,
+        };
+        let h = harmonize_melody(&melody, "C", "major", &options);
+        let beats: Vec<f32> = h.segments.iter().map(|s| s.beat).collect();
+        assert_eq!(beats, vec![1.0, 7.0, 13.0]);
+        assert_eq!(h.progression.beats(), 8.0);
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::key::TONICS;
+use crate::naming::Style;
+use crate::progression;
+use crate::scale;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+// Costs of chord outside of key, key change and tonic chord
+const OUTSIDE: i32 = 2;
+const CHANGE: i32 = 4;
+const TONIC: i32 = -2;
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct KeyArea {
+    /// Tonic note
+    pub key: String,
+    pub scale: String,
+    /// Index of first chord
+    pub start: usize,
+    /// Index past the last chord
+    pub end: usize,
+    /// Roman numerals of chords in key
+    pub numerals: Vec<String>,
+}
+
+impl fmt::Display for KeyArea {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:>3}-{1:<3} {2:<3} {3:<14} {4:}",
+            self.start + 1,
+            self.end,
+            &self.key,
+            scale::friendly_name(&self.scale),
+            &self.numerals.join(" ")
+        )
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Modulation {
+    /// Index of first chord in new key
+    pub index: usize,
+    /// Key areas before and after, as indexes of areas
+    pub from: usize,
+    pub to: usize,
+    /// Pivot, direct or chromatic
+    pub kind: String,
+    /// Index of chord belonging to both keys
+    pub pivot: Option<usize>,
+    /// Numerals of pivot chord in old and new key
+    pub pivot_numerals: Vec<String>,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ModulationAnalysis {
+    pub areas: Vec<KeyArea>,
+    pub modulations: Vec<Modulation>,
+}
+
+impl fmt::Display for ModulationAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Key areas:")?;
+        for area in &self.areas {
+            writeln!(f, "{}", area)?;
+        }
+
+        writeln!(f, "Modulations:")?;
+        for m in &self.modulations {
+            let (from, to) = (&self.areas[m.from], &self.areas[m.to]);
+            write!(
+                f,
+                "{0:>3}  {1:} {2:} -> {3:} {4:}  {5:}",
+                m.index + 1,
+                &from.key,
+                scale::friendly_name(&from.scale),
+                &to.key,
+                scale::friendly_name(&to.scale),
+                &m.kind
+            )?;
+            if let Some(pivot) = m.pivot {
+                write!(f, " at {}: {}", pivot + 1, m.pivot_numerals.join(" = "))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// Key with the chords analyze finds in it, as sorted pitch classes
+struct KeyChords {
+    tonic: (char, i8),
+    scale: &'static str,
+    chords: Vec<Vec<u8>>,
+}
+
+impl KeyChords {
+    // Minor keys include chords of harmonic minor
+    fn new(tonic: (char, i8), scale: &'static str) -> KeyChords {
+        let key = util::note_to_str(tonic);
+        let mut sources = vec![scale];
+        if scale == "minor" {
+            sources.push("harmonicminor");
+        }
+
+        let mut chords: Vec<Vec<u8>> = vec![];
+        for source in sources {
+            for chord in crate::analyze(&key, source, true).1 {
+                let pcs = sorted(&chord);
+                if !chords.contains(&pcs) {
+                    chords.push(pcs);
+                }
+            }
+        }
+
+        KeyChords {
+            tonic,
+            scale,
+            chords,
+        }
+    }
+
+    fn contains(&self, chord: &Chord) -> bool {
+        self.chords.contains(&sorted(chord))
+    }
+
+    fn is_tonic(&self, chord: &Chord) -> bool {
+        chord.root() == self.tonic && self.contains(chord)
+    }
+
+    fn numeral(&self, chord: &Chord) -> String {
+        chord.styled_name(
+            Style::Roman,
+            &progression::degree_notes(self.tonic, self.scale),
+        )
+    }
+}
+
+fn sorted(chord: &Chord) -> Vec<u8> {
+    let mut pcs = chord.pitch_classes();
+    pcs.sort_unstable();
+    pcs.dedup();
+    pcs
+}
+
+// Chromatic inflection: same letter with another accidental
+fn chromatic(a: &Chord, b: &Chord) -> bool {
+    a.notes.iter().any(|x| {
+        let x = util::str_to_note(x);
+        b.notes.iter().any(|y| {
+            let y = util::str_to_note(y);
+            x.0 == y.0 && x.1 != y.1
+        })
+    })
+}
+
+/// Splits chords into major and minor key areas, keeping the key while
+/// chords fit it, and classifies the modulations between them.
+/// A pivot modulation has a chord of both keys before the first chord
+/// foreign to the old key, a chromatic one alters a note of the
+/// previous chord, others are direct.
+pub fn detect(chords: &[Chord]) -> ModulationAnalysis {
+    let mut result = ModulationAnalysis::default();
+    if chords.is_empty() {
+        return result;
+    }
+
+    let keys: Vec<KeyChords> = ["major", "minor"]
+        .iter()
+        .flat_map(|&scale| {
+            TONICS.iter().enumerate().map(move |(pc, &spelling)| {
+                // follow spelling of chords for tonic
+                let tonic = chords
+                    .iter()
+                    .map(|c| c.root())
+                    .find(|&r| util::pitch_class(r) == pc as u8)
+                    .unwrap_or(spelling);
+                KeyChords::new(tonic, scale)
+            })
+        })
+        .collect();
+
+    let cost = |k: &KeyChords, c: &Chord| {
+        if k.is_tonic(c) {
+            TONIC
+        } else if k.contains(c) {
+            0
+        } else {
+            OUTSIDE
+        }
+    };
+
+    // least cost key of each chord, ties going to the earlier key.
+    // Starting on tonic counts twice, as pieces open in their key.
+    let mut costs: Vec<i32> = keys
+        .iter()
+        .map(|k| cost(k, &chords[0]) + if k.is_tonic(&chords[0]) { TONIC } else { 0 })
+        .collect();
+    let mut back: Vec<Vec<usize>> = vec![];
+
+    for chord in &chords[1..] {
+        let best = (0..keys.len()).min_by_key(|&k| costs[k]).unwrap();
+        let mut next = vec![];
+        let mut from = vec![];
+
+        for (k, key) in keys.iter().enumerate() {
+            let (prev, c) = if costs[k] <= costs[best] + CHANGE {
+                (k, costs[k])
+            } else {
+                (best, costs[best] + CHANGE)
+            };
+            next.push(c + cost(key, chord));
+            from.push(prev);
+        }
+
+        costs = next;
+        back.push(from);
+    }
+
+    let mut path = vec![(0..keys.len()).min_by_key(|&k| costs[k]).unwrap()];
+    for from in back.iter().rev() {
+        path.push(from[path[path.len() - 1]]);
+    }
+    path.reverse();
+
+    // areas of consecutive chords in the same key
+    let mut start = 0;
+    for i in 1..=chords.len() {
+        if i == chords.len() || path[i] != path[start] {
+            let key = &keys[path[start]];
+            result.areas.push(KeyArea {
+                key: util::note_to_str(key.tonic).to_uppercase(),
+                scale: key.scale.to_string(),
+                start,
+                end: i,
+                numerals: chords[start..i].iter().map(|c| key.numeral(c)).collect(),
+            });
+            start = i;
+        }
+    }
+
+    for to in 1..result.areas.len() {
+        let from = to - 1;
+        let (old, new) = (
+            &keys[path[result.areas[from].start]],
+            &keys[path[result.areas[to].start]],
+        );
+        let index = result.areas[to].start;
+
+        // first chord foreign to the old key
+        let foreign = (index..result.areas[to].end)
+            .find(|&i| !old.contains(&chords[i]))
+            .unwrap_or(index);
+
+        let pivot = (result.areas[from].start..foreign)
+            .next_back()
+            .filter(|&i| old.contains(&chords[i]) && new.contains(&chords[i]));
+
+        let (kind, pivot_numerals) = match pivot {
+            Some(p) => (
+                "pivot",
+                vec![old.numeral(&chords[p]), new.numeral(&chords[p])],
+            ),
+            None if chromatic(&chords[foreign - 1], &chords[foreign]) => ("chromatic", vec![]),
+            None => ("direct", vec![]),
+        };
+
+        result.modulations.push(Modulation {
+            index,
+            from,
+            to,
+            kind: kind.to_string(),
+            pivot,
+            pivot_numerals,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progression::parse_symbol;
+
+    fn chords(symbols: &str) -> Vec<Chord> {
+        symbols
+            .split_whitespace()
+            .map(|s| parse_symbol(s).unwrap().chord)
+            .collect()
+    }
+
+    #[test]
+    fn pivot_modulation_to_dominant() {
+        let analysis = detect(&chords("C F G7 C Am D G D7 G"));
+
+        assert_eq!(analysis.areas.len(), 2);
+        assert_eq!(analysis.areas[1].key, "G");
+        assert_eq!(analysis.areas[1].start, 4);
+
+        let m = &analysis.modulations[0];
+        assert_eq!(m.kind, "pivot");
+        assert_eq!(m.pivot, Some(4));
+        // Am is vi of C and ii of G
+        assert_eq!(m.pivot_numerals, vec!["vi", "ii"]);
+    }
+
+    #[test]
+    fn direct_modulation_up_half_step() {
+        let analysis = detect(&chords("C Dm G7 C D♭ G♭ A♭7 D♭"));
+
+        assert_eq!(analysis.areas[1].key, "D♭");
+        assert_eq!(analysis.areas[1].numerals, vec!["I", "IV", "V7", "I"]);
+        assert_eq!(analysis.modulations[0].kind, "direct");
+        assert_eq!(analysis.modulations[0].pivot, None);
+    }
+}
@@ -0,0 +1,613 @@
+use crate::attribute::Attributes;
+use crate::util;
+
+#[derive(PartialEq, Clone, Copy, Default)]
+pub enum Style {
+    #[default]
+    Default,
+    Jazz,
+    Pop,
+    Berklee,
+    Roman,
+}
+
+impl From<Style> for String {
+    fn from(style: Style) -> Self {
+        match style {
+            Style::Default => "default",
+            Style::Jazz => "jazz",
+            Style::Pop => "pop",
+            Style::Berklee => "berklee",
+            Style::Roman => "roman",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Style {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "jazz" => Style::Jazz,
+            "pop" => Style::Pop,
+            "berklee" => Style::Berklee,
+            "roman" | "classical" => Style::Roman,
+            _ => Style::Default,
+        }
+    }
+}
+
+pub fn supported_styles() -> Vec<String> {
+    ["default", "jazz", "pop", "berklee", "roman"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Triad {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Sus2,
+    Sus4,
+    Power,
+    Other,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Seventh {
+    None,
+    Minor,
+    Major,
+    Diminished,
+    Sixth,
+}
+
+// Chord structure resolved from intervals
+struct Structure {
+    triad: Triad,
+    seventh: Seventh,
+    /// Tensions above the seventh, as 9, 11 and 13 with alterations
+    tensions: Vec<&'static str>,
+    no3: bool,
+    no5: bool,
+}
+
+impl Structure {
+    fn new(intervals: &[u8]) -> Structure {
+        let has = |i: u8| intervals.contains(&i);
+
+        let third = has(3) || has(4);
+        let fifth = has(7);
+
+        // with both thirds the minor one is read as ♯9,
+        // unless it spells a diminished triad (eg. vii° of harmonic minor)
+        let diminished = has(3) && has(6) && !fifth;
+        let triad = if has(4) && !diminished {
+            if has(8) && !fifth {
+                Triad::Augmented
+            } else {
+                Triad::Major
+            }
+        } else if has(3) {
+            if has(6) && !fifth {
+                Triad::Diminished
+            } else {
+                Triad::Minor
+            }
+        } else if has(5) {
+            Triad::Sus4
+        } else if has(2) {
+            Triad::Sus2
+        } else if fifth {
+            Triad::Power
+        } else {
+            Triad::Other
+        };
+
+        let seventh = if has(10) {
+            Seventh::Minor
+        } else if has(11) {
+            Seventh::Major
+        } else if has(9) && triad == Triad::Diminished {
+            Seventh::Diminished
+        } else if has(9) {
+            Seventh::Sixth
+        } else {
+            Seventh::None
+        };
+
+        let mut tensions = vec![];
+        if has(1) {
+            tensions.push("b9");
+        }
+        if has(2) && triad != Triad::Sus2 {
+            tensions.push("9");
+        }
+        if has(3) && has(4) {
+            tensions.push(if triad == Triad::Diminished {
+                "b11"
+            } else {
+                "#9"
+            });
+        }
+        if has(5) && triad != Triad::Sus4 {
+            tensions.push("11");
+        }
+        if has(6) && triad != Triad::Diminished {
+            tensions.push("#11");
+        }
+        if has(8) && triad != Triad::Augmented {
+            tensions.push("b13");
+        }
+        if has(9) && matches!(seventh, Seventh::Minor | Seventh::Major) {
+            tensions.push("13");
+        }
+
+        Structure {
+            no3: !third
+                && matches!(triad, Triad::Power | Triad::Other)
+                && matches!(seventh, Seventh::Minor | Seventh::Major),
+            no5: third && !(has(6) || has(7) || has(8)),
+            triad,
+            seventh,
+            tensions,
+        }
+    }
+
+    fn has(&self, tension: &str) -> bool {
+        self.tensions.contains(&tension)
+    }
+
+    // Highest stacked natural extension, and tensions left over
+    fn stacked(&self) -> (&'static str, Vec<&'static str>) {
+        let number = if self.has("9") && self.has("13") {
+            "13"
+        } else if self.has("9") && self.has("11") {
+            "11"
+        } else if self.has("9") {
+            "9"
+        } else {
+            "7"
+        };
+
+        let consumed: &[&str] = match number {
+            "13" => &["9", "11", "13"],
+            "11" => &["9", "11"],
+            "9" => &["9"],
+            _ => &[],
+        };
+
+        (
+            number,
+            self.tensions
+                .iter()
+                .filter(|t| !consumed.contains(t))
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+// Replaces accidentals with music symbols
+fn symbols(s: &str) -> String {
+    s.replace('b', "♭").replace('#', "♯")
+}
+
+fn pop(s: &Structure) -> String {
+    let (number, rest) = s.stacked();
+
+    let mut val = match (s.triad, s.seventh) {
+        (Triad::Major, Seventh::Minor) => number.to_string(),
+        (Triad::Major, Seventh::Major) => format!("maj{}", number),
+        (Triad::Minor, Seventh::Minor) => format!("m{}", number),
+        (Triad::Minor, Seventh::Major) => format!("m(maj{})", number),
+        (Triad::Diminished, Seventh::Minor) => format!("m{}b5", number),
+        (Triad::Diminished, Seventh::Diminished) => "dim7".to_string(),
+        (Triad::Augmented, Seventh::Minor) => format!("{}#5", number),
+        (Triad::Augmented, Seventh::Major) => format!("maj{}#5", number),
+        (Triad::Sus2, Seventh::Minor) => format!("{}sus2", number),
+        (Triad::Sus4, Seventh::Minor) => format!("{}sus4", number),
+        (Triad::Sus2, Seventh::Major) => format!("maj{}sus2", number),
+        (Triad::Sus4, Seventh::Major) => format!("maj{}sus4", number),
+        (Triad::Power, Seventh::Minor) | (Triad::Other, Seventh::Minor) => number.to_string(),
+        (Triad::Power, Seventh::Major) | (Triad::Other, Seventh::Major) => {
+            format!("maj{}", number)
+        }
+        (Triad::Minor, Seventh::Sixth) => "m6".to_string(),
+        (_, Seventh::Sixth) => "6".to_string(),
+        (Triad::Minor, _) => "m".to_string(),
+        (Triad::Diminished, _) => "dim".to_string(),
+        (Triad::Augmented, _) => "aug".to_string(),
+        (Triad::Sus2, _) => "sus2".to_string(),
+        (Triad::Sus4, _) => "sus4".to_string(),
+        (Triad::Power, _) => "5".to_string(),
+        _ => String::new(),
+    };
+
+    let tensions = if s.seventh == Seventh::None || s.seventh == Seventh::Sixth {
+        &s.tensions
+    } else {
+        &rest
+    };
+
+    for t in tensions {
+        if s.seventh == Seventh::Sixth && *t == "9" {
+            val.push_str("/9");
+        } else if s.seventh != Seventh::None && t.len() > 1 && !t.starts_with('1') {
+            // altered tensions follow the chord number, never the root
+            val.push_str(t);
+        } else {
+            val.push_str("add");
+            val.push_str(t);
+        }
+    }
+
+    if s.no3 {
+        val.push_str("no3");
+    }
+    if s.no5 {
+        val.push_str("no5");
+    }
+
+    val
+}
+
+fn jazz(s: &Structure) -> String {
+    let (number, rest) = s.stacked();
+
+    let mut val = match (s.triad, s.seventh) {
+        (Triad::Major, Seventh::Minor) => number.to_string(),
+        (Triad::Major, Seventh::Major) => format!("Δ{}", number),
+        (Triad::Minor, Seventh::Minor) => format!("−{}", number),
+        (Triad::Minor, Seventh::Major) => format!("−Δ{}", number),
+        (Triad::Diminished, Seventh::Minor) => format!("ø{}", number),
+        (Triad::Diminished, Seventh::Diminished) => "°7".to_string(),
+        (Triad::Augmented, Seventh::Minor) => format!("+{}", number),
+        (Triad::Augmented, Seventh::Major) => format!("+Δ{}", number),
+        (Triad::Sus2, Seventh::Minor) => format!("{}sus2", number),
+        (Triad::Sus4, Seventh::Minor) => format!("{}sus", number),
+        (Triad::Sus2, Seventh::Major) => format!("Δ{}sus2", number),
+        (Triad::Sus4, Seventh::Major) => format!("Δ{}sus", number),
+        (Triad::Power, Seventh::Minor) | (Triad::Other, Seventh::Minor) => number.to_string(),
+        (Triad::Power, Seventh::Major) | (Triad::Other, Seventh::Major) => {
+            format!("Δ{}", number)
+        }
+        (Triad::Minor, Seventh::Sixth) => "−6".to_string(),
+        (_, Seventh::Sixth) => "6".to_string(),
+        (Triad::Minor, _) => "−".to_string(),
+        (Triad::Diminished, _) => "°".to_string(),
+        (Triad::Augmented, _) => "+".to_string(),
+        (Triad::Sus2, _) => "sus2".to_string(),
+        (Triad::Sus4, _) => "sus".to_string(),
+        (Triad::Power, _) => "5".to_string(),
+        _ => String::new(),
+    };
+
+    let tensions = match s.seventh {
+        Seventh::None | Seventh::Sixth => s.tensions.clone(),
+        _ => rest,
+    };
+
+    if !tensions.is_empty() {
+        let tensions: Vec<String> = tensions.iter().map(|t| symbols(t)).collect();
+        if s.seventh == Seventh::None {
+            val.push_str(&format!("(add{})", tensions.join(",")));
+        } else {
+            val.push_str(&format!("({})", tensions.join(",")));
+        }
+    }
+
+    if s.no3 {
+        val.push_str("(no3)");
+    }
+    if s.no5 {
+        val.push_str("(no5)");
+    }
+
+    val
+}
+
+fn berklee(s: &Structure) -> String {
+    let mut val = match (s.triad, s.seventh) {
+        (Triad::Major, Seventh::Minor) => "7",
+        (Triad::Major, Seventh::Major) => "maj7",
+        (Triad::Minor, Seventh::Minor) => "-7",
+        (Triad::Minor, Seventh::Major) => "-maj7",
+        (Triad::Diminished, Seventh::Minor) => "-7(b5)",
+        (Triad::Diminished, Seventh::Diminished) => "o7",
+        (Triad::Augmented, Seventh::Minor) => "+7",
+        (Triad::Augmented, Seventh::Major) => "+maj7",
+        (Triad::Sus2, Seventh::Minor) => "7sus2",
+        (Triad::Sus4, Seventh::Minor) => "7sus4",
+        (Triad::Sus2, Seventh::Major) => "maj7sus2",
+        (Triad::Sus4, Seventh::Major) => "maj7sus4",
+        (Triad::Power, Seventh::Minor) | (Triad::Other, Seventh::Minor) => "7",
+        (Triad::Power, Seventh::Major) | (Triad::Other, Seventh::Major) => "maj7",
+        (Triad::Minor, Seventh::Sixth) => "-6",
+        (_, Seventh::Sixth) => "6",
+        (Triad::Minor, _) => "-",
+        (Triad::Diminished, _) => "o",
+        (Triad::Augmented, _) => "+",
+        (Triad::Sus2, _) => "sus2",
+        (Triad::Sus4, _) => "sus4",
+        (Triad::Power, _) => "5",
+        _ => "",
+    }
+    .to_string();
+
+    // tensions are always listed, never stacked into the chord number
+    if !s.tensions.is_empty() {
+        if s.seventh == Seventh::None {
+            val.push_str(&format!("(add{})", s.tensions.join(",")));
+        } else {
+            val.push_str(&format!("({})", s.tensions.join(",")));
+        }
+    }
+
+    if s.no3 {
+        val.push_str("(omit3)");
+    }
+    if s.no5 {
+        val.push_str("(omit5)");
+    }
+
+    val
+}
+
+fn numeral(degree: usize) -> &'static str {
+    ["I", "II", "III", "IV", "V", "VI", "VII"][degree % 7]
+}
+
+/// Returns roman numeral degree of pitch class in key, with
+/// accidental relative to major scale for chromatic roots (eg. ♭VII).
+/// None without key notes.
+pub fn degree(pc: u8, key: &[(char, i8)]) -> Option<String> {
+    let tonic = util::pitch_class(*key.first()?);
+    let offset = (pc + 12 - tonic) % 12;
+
+    if key.len() == 7 {
+        if let Some(d) = key.iter().position(|&n| util::pitch_class(n) == pc % 12) {
+            return Some(numeral(d).to_string());
+        }
+    }
+
+    let major = [0, 2, 4, 5, 7, 9, 11];
+    Some(match major.iter().position(|&m| m == offset) {
+        Some(d) => numeral(d).to_string(),
+        None => match major.iter().position(|&m| m == (offset + 1) % 12) {
+            Some(d) => format!("♭{}", numeral(d)),
+            None => format!(
+                "♯{}",
+                numeral(major.iter().position(|&m| m == offset - 1).unwrap())
+            ),
+        },
+    })
+}
+
+// Chords without a third take the case of the third above their root
+// in key, minor when the root is chromatic
+fn minor_in_key(root: u8, key: &[(char, i8)]) -> bool {
+    let pcs: Vec<u8> = key.iter().map(|&n| util::pitch_class(n)).collect();
+    match pcs.iter().position(|&pc| pc == root) {
+        Some(d) if pcs.len() == 7 => (pcs[(d + 2) % 7] + 12 - root) % 12 == 3,
+        _ => true,
+    }
+}
+
+fn roman(s: &Structure, root: u8, key: &[(char, i8)]) -> Option<String> {
+    // sixth chords are first inversion sevenths of the chord a sixth above
+    if s.seventh == Seventh::Sixth && s.tensions.is_empty() {
+        let triad = if s.triad == Triad::Minor {
+            Triad::Diminished
+        } else {
+            Triad::Minor
+        };
+        let sixth = Structure {
+            triad,
+            seventh: Seventh::Minor,
+            tensions: vec![],
+            no3: false,
+            no5: false,
+        };
+        return Some(format!(
+            "{}6/5",
+            roman(&sixth, (root + 9) % 12, key)?.trim_end_matches('7')
+        ));
+    }
+
+    let degree = degree(root, key)?;
+    let minor = match s.triad {
+        Triad::Minor | Triad::Diminished => true,
+        _ => s.no3 && minor_in_key(root, key),
+    };
+    let mut val = if minor { degree.to_lowercase() } else { degree };
+
+    val.push_str(match (s.triad, s.seventh) {
+        (Triad::Diminished, Seventh::Minor) => "ø",
+        (Triad::Diminished, _) => "°",
+        (Triad::Augmented, _) => "+",
+        _ => "",
+    });
+
+    // figured bass of root position, major sevenths marked as such
+    let (number, rest) = s.stacked();
+    match s.seventh {
+        Seventh::None | Seventh::Sixth => {}
+        Seventh::Major => val.push_str(&format!("M{}", number)),
+        _ => val.push_str(number),
+    }
+
+    val.push_str(match s.triad {
+        Triad::Sus2 => "sus2",
+        Triad::Sus4 => "sus4",
+        _ => "",
+    });
+
+    match s.seventh {
+        Seventh::None if !s.tensions.is_empty() => {
+            val.push_str(&format!("(add{})", symbols(&s.tensions.join(","))))
+        }
+        Seventh::Sixth => val.push_str(&format!("(add6,{})", symbols(&s.tensions.join(",")))),
+        Seventh::None => {}
+        _ if !rest.is_empty() => val.push_str(&format!("({})", symbols(&rest.join(",")))),
+        _ => {}
+    }
+
+    if s.no3 {
+        val.push_str("(no3)");
+    }
+
+    Some(val)
+}
+
+/// Names chord stacked in fourths, fifths or seconds by its structure
+/// (eg. "C quartal (4 voices)"), alike in every style. None for chords
+/// that aren't such stacks.
+pub fn stacked_name(
+    root: (char, i8),
+    intervals: &[u8],
+    style: Style,
+    key: &[(char, i8)],
+) -> Option<String> {
+    let mut attr = Attributes::new();
+    for &i in intervals {
+        attr.push_interval(i);
+    }
+
+    let stacked = attr.resolve_stacked()?;
+    let degree = match style {
+        Style::Roman => degree(util::pitch_class(root), key),
+        _ => None,
+    };
+    Some(format!(
+        "{}{}",
+        degree.unwrap_or_else(|| util::note_to_str(root).to_uppercase()),
+        stacked
+    ))
+}
+
+/// Names chord from root and intervals in given style.
+/// Roman numerals are relative to given key notes, tonic first,
+/// falling back to pop symbols without key notes.
+/// Default style is not resolved here, as it comes from chord attributes.
+pub fn name(root: (char, i8), intervals: &[u8], style: Style, key: &[(char, i8)]) -> String {
+    let s = Structure::new(intervals);
+
+    match style {
+        Style::Roman => roman(&s, util::pitch_class(root), key)
+            .unwrap_or_else(|| format!("{}{}", util::note_to_str(root).to_uppercase(), pop(&s))),
+        Style::Jazz => format!("{}{}", util::note_to_str(root).to_uppercase(), jazz(&s)),
+        Style::Berklee => format!("{}{}", util::note_to_str(root).to_uppercase(), berklee(&s)),
+        _ => format!("{}{}", util::note_to_str(root).to_uppercase(), pop(&s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const C_MAJOR: [(char, i8); 7] = [
+        ('c', 0),
+        ('d', 0),
+        ('e', 0),
+        ('f', 0),
+        ('g', 0),
+        ('a', 0),
+        ('b', 0),
+    ];
+
+    fn names(style: Style) -> Vec<String> {
+        [
+            (('c', 0), vec![0, 4, 7, 11]),
+            (('d', 0), vec![0, 3, 7, 10]),
+            (('b', 0), vec![0, 3, 6, 10]),
+            (('c', 0), vec![0, 3, 6, 9]),
+            (('g', 0), vec![0, 5, 7, 10]),
+        ]
+        .iter()
+        .map(|(root, intervals)| name(*root, intervals, style, &C_MAJOR))
+        .collect()
+    }
+
+    #[test]
+    fn chord_symbols_by_style() {
+        assert_eq!(
+            names(Style::Jazz),
+            vec!["CΔ7", "D−7", "Bø7", "C°7", "G7sus"]
+        );
+        assert_eq!(
+            names(Style::Pop),
+            vec!["Cmaj7", "Dm7", "Bm7b5", "Cdim7", "G7sus4"]
+        );
+        assert_eq!(
+            names(Style::Berklee),
+            vec!["Cmaj7", "D-7", "B-7(b5)", "Co7", "G7sus4"]
+        );
+    }
+
+    #[test]
+    fn roman_numerals_in_key() {
+        let roman = |root, intervals: &[u8]| name(root, intervals, Style::Roman, &C_MAJOR);
+        assert_eq!(roman(('d', 0), &[0, 3, 7, 10]), "ii7");
+        assert_eq!(roman(('g', 0), &[0, 4, 7, 10]), "V7");
+        assert_eq!(roman(('b', 0), &[0, 3, 6, 10]), "viiø7");
+        assert_eq!(roman(('b', -1), &[0, 4, 7]), "♭VII");
+        assert_eq!(roman(('c', 0), &[0, 4, 8]), "I+");
+    }
+
+    #[test]
+    fn chromatic_degrees_relative_to_major() {
+        let c_minor = [
+            ('c', 0),
+            ('d', 0),
+            ('e', -1),
+            ('f', 0),
+            ('g', 0),
+            ('a', -1),
+            ('b', -1),
+        ];
+        assert_eq!(degree(3, &c_minor).as_deref(), Some("III"));
+        assert_eq!(degree(1, &c_minor).as_deref(), Some("♭II"));
+        assert_eq!(degree(1, &c_minor[..1]).as_deref(), Some("♭II"));
+        assert_eq!(degree(11, &c_minor[..1]).as_deref(), Some("VII"));
+        assert_eq!(degree(0, &[]), None);
+    }
+
+    #[test]
+    fn roman_sevenths_and_added_tones() {
+        let roman = |root, intervals: &[u8]| name(root, intervals, Style::Roman, &C_MAJOR);
+        assert_eq!(roman(('c', 0), &[0, 4, 7, 11]), "IM7");
+        assert_eq!(roman(('c', 0), &[0, 4, 7, 10]), "I7");
+        assert_eq!(roman(('c', 0), &[0, 4, 7, 2]), "I(add9)");
+        assert_eq!(roman(('c', 0), &[0, 4, 7, 5]), "I(add11)");
+        assert_eq!(roman(('d', 0), &[0, 7, 10]), "ii7(no3)");
+        assert_eq!(roman(('g', 0), &[0, 7, 10]), "V7(no3)");
+    }
+
+    #[test]
+    fn roman_falls_back_to_symbol_without_key() {
+        assert_eq!(named(&[0, 4, 7, 10], Style::Roman), "C7");
+    }
+
+    fn named(intervals: &[u8], style: Style) -> String {
+        name(('c', 0), intervals, style, &[])
+    }
+
+    #[test]
+    fn sharp_nine_keeps_major_third() {
+        assert_eq!(named(&[0, 4, 7, 10, 3], Style::Jazz), "C7(♯9)");
+        assert_eq!(named(&[0, 4, 7, 10, 3], Style::Pop), "C7#9");
+        assert_eq!(named(&[0, 4, 7, 11, 3], Style::Jazz), "CΔ7(♯9)");
+    }
+
+    #[test]
+    fn added_tensions_on_triads() {
+        assert_eq!(named(&[0, 4, 6, 7], Style::Pop), "Cadd#11");
+        assert_eq!(named(&[0, 4, 6, 7], Style::Jazz), "C(add♯11)");
+        assert_eq!(named(&[0, 4, 7, 2], Style::Pop), "Cadd9");
+        assert_eq!(named(&[0, 4, 7, 1], Style::Jazz), "C(add♭9)");
+        assert_eq!(named(&[0, 4, 7, 2, 5], Style::Jazz), "C(add9,11)");
+    }
+}
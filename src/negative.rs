@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::progression::{self, Progression, ProgressionChord};
+use crate::root::{self, RootMethod};
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+/// Axis of reflection, lying midway between two notes
+/// (eg. C and G give the axis between E♭ and E)
+#[derive(PartialEq, Clone, Copy)]
+pub struct Axis {
+    pub low: (char, i8),
+    pub high: (char, i8),
+}
+
+impl Axis {
+    /// Axis between tonic and dominant of key
+    pub fn new(key: &str) -> Axis {
+        let tonic = util::str_to_note(key);
+        Axis {
+            low: tonic,
+            high: util::spell_interval(tonic, 7, 4),
+        }
+    }
+
+    /// Parses axis of two notes (eg. "C:G")
+    pub fn parse(input: &str) -> Result<Axis, String> {
+        let error = || format!("Invalid axis: {}", input);
+        let note = |s: &str| {
+            let n = s.chars().next().map(|_| util::str_to_note(s));
+            n.filter(|n| ('a'..='g').contains(&n.0) && util::note_to_str(*n).len() >= s.len())
+        };
+
+        let (low, high) = input.split_once(':').ok_or_else(error)?;
+        match (note(low), note(high)) {
+            (Some(low), Some(high)) => Ok(Axis { low, high }),
+            _ => Err(error()),
+        }
+    }
+
+    /// Reflects note around axis, letter names included
+    /// (eg. E -> E♭ and D -> F around C:G)
+    pub fn reflect(self, note: (char, i8)) -> (char, i8) {
+        let letter = |n: (char, i8)| (n.0 as u8 - b'a' + 5) % 7;
+        let pc = (util::pitch_class(self.low) + util::pitch_class(self.high) + 24
+            - util::pitch_class(note))
+            % 12;
+        let reflected = (letter(self.low) + letter(self.high) + 14 - letter(note)) % 7;
+        let natural = (b'a' + (reflected + 2) % 7) as char;
+        let alt = (pc as i8 - util::pitch_class((natural, 0)) as i8 + 18).rem_euclid(12) - 6;
+
+        if alt.abs() <= 1 {
+            (natural, alt)
+        } else {
+            util::spell_pitch_class(pc, &[], note.1 < 0)
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct NegativeChord {
+    pub chord: Chord,
+    pub negative: Chord,
+}
+
+impl fmt::Display for NegativeChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{0:<18} -> {1:}", &self.chord.name, &self.negative)
+    }
+}
+
+/// Reflects chord around axis. The root of the result is the
+/// chord tone perceived as root, as found by Parncutt's method.
+pub fn negative(chord: &Chord, axis: Axis) -> Chord {
+    let notes: Vec<(char, i8)> = chord
+        .notes
+        .iter()
+        .map(|n| axis.reflect(util::str_to_note(n)))
+        .collect();
+    let pcs: Vec<u8> = notes.iter().map(|&n| util::pitch_class(n)).collect();
+
+    let analysis = root::find_root(&notes, RootMethod::Parncutt);
+    let root = analysis
+        .candidates
+        .iter()
+        .map(|c| util::str_to_note(&c.note))
+        .find(|r| pcs.contains(&util::pitch_class(*r)))
+        .and_then(|r| {
+            notes
+                .iter()
+                .find(|n| util::pitch_class(**n) == util::pitch_class(r))
+        })
+        .copied()
+        .unwrap_or(notes[notes.len() - 1]);
+
+    let root_pc = util::pitch_class(root);
+    let mut intervals: Vec<u8> = pcs.iter().map(|pc| (pc + 12 - root_pc) % 12).collect();
+    intervals.sort_unstable();
+    intervals.dedup();
+
+    Chord::from_intervals(root, intervals, &notes)
+}
+
+/// Maps each chord analyze finds in key and scale to its negative.
+/// Chords are spelled by letter in key first, for the reflection
+/// to keep letter names, so scale has to have seven notes.
+pub fn negative_chords(key: &str, scale: &str, axis: Axis) -> Result<Vec<NegativeChord>, String> {
+    let notes = progression::heptatonic_notes(util::str_to_note(key), scale)?;
+    let flats = notes.iter().any(|n| n.1 < 0);
+
+    Ok(crate::analyze(key, scale, false)
+        .1
+        .into_iter()
+        .map(|c| {
+            let root = util::spell_pitch_class(util::pitch_class(c.root()), &notes, flats);
+            let chord = Chord::from_intervals(root, c.intervals(), &notes);
+            NegativeChord {
+                negative: negative(&chord, axis),
+                chord,
+            }
+        })
+        .collect())
+}
+
+/// Maps progression to its negative, keeping lengths. Numerals are
+/// those of the new chords in the key of the progression.
+pub fn negative_progression(progression: &Progression, axis: Axis) -> Progression {
+    let key = progression.key_notes();
+
+    Progression {
+        key: progression.key.clone(),
+        scale: progression.scale.clone(),
+        chords: progression
+            .chords
+            .iter()
+            .map(|c| {
+                let chord = negative(&c.chord, axis);
+                ProgressionChord {
+                    numeral: chord.styled_name(Style::Roman, &key),
+                    bass: c
+                        .bass
+                        .as_ref()
+                        .map(|b| util::note_to_str(axis.reflect(util::str_to_note(b)))),
+                    chord,
+                    beats: c.beats,
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflects_around_tonic_and_dominant() {
+        let axis = Axis::new("c");
+        assert!(axis.reflect(('e', 0)) == ('e', -1));
+        assert!(axis.reflect(('d', 0)) == ('f', 0));
+        let g7 = Chord::from_intervals(('g', 0), vec![0, 4, 7, 10], &[]);
+        assert_eq!(negative(&g7, axis).name, "Fm6");
+    }
+
+    #[test]
+    fn negative_progression_keeps_lengths() {
+        let p = Progression::parse("ii7 V7:2 I", "C", "major").unwrap();
+        let n = negative_progression(&p, Axis::new("c"));
+        let beats: Vec<f32> = n.chords.iter().map(|c| c.beats).collect();
+
+        assert_eq!(beats, vec![4.0, 2.0, 4.0]);
+        assert_eq!(n.chords[2].chord.name, "Cm");
+    }
+
+    #[test]
+    fn parses_axis() {
+        let axis = Axis::parse("D:A").unwrap();
+        assert!(axis.low == ('d', 0) && axis.high == ('a', 0));
+    }
+
+    #[test]
+    fn rejects_invalid_axis() {
+        assert!(Axis::parse("C:Q").is_err());
+        assert!(Axis::parse("C").is_err());
+        assert!(Axis::parse("D:A").is_ok());
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Transform {
+    /// Parallel: C <-> Cm
+    P,
+    /// Leading tone exchange: C <-> Em
+    L,
+    /// Relative: C <-> Am
+    R,
+    /// Nebenverwandt, RLP: C <-> Fm
+    N,
+    /// Slide, LPR: C <-> C#m
+    S,
+    /// Hexatonic pole, LPL: C <-> A♭m
+    H,
+}
+
+impl From<Transform> for String {
+    fn from(transform: Transform) -> Self {
+        match transform {
+            Transform::P => "P",
+            Transform::L => "L",
+            Transform::R => "R",
+            Transform::N => "N",
+            Transform::S => "S",
+            Transform::H => "H",
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for Transform {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match &s.to_lowercase()[..] {
+            "p" | "parallel" => Ok(Transform::P),
+            "l" | "leittonwechsel" => Ok(Transform::L),
+            "r" | "relative" => Ok(Transform::R),
+            "n" | "nebenverwandt" => Ok(Transform::N),
+            "s" | "slide" => Ok(Transform::S),
+            "h" | "hexatonic" => Ok(Transform::H),
+            _ => Err(format!("Unknown transform: {}", s)),
+        }
+    }
+}
+
+impl Transform {
+    /// Semitones and letters the root moves, from major and from minor
+    fn root_motion(self) -> [(u8, u8); 2] {
+        match self {
+            Transform::P => [(0, 0), (0, 0)],
+            Transform::L => [(4, 2), (8, 5)],
+            Transform::R => [(9, 5), (3, 2)],
+            Transform::N => [(5, 3), (7, 4)],
+            Transform::S => [(1, 0), (11, 6)],
+            Transform::H => [(8, 5), (4, 2)],
+        }
+    }
+}
+
+pub fn supported_transforms() -> Vec<String> {
+    ["P", "L", "R", "N", "S", "H"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+const TRANSFORMS: [Transform; 6] = [
+    Transform::P,
+    Transform::L,
+    Transform::R,
+    Transform::N,
+    Transform::S,
+    Transform::H,
+];
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TransformStep {
+    pub transform: String,
+    pub chord: Chord,
+}
+
+impl fmt::Display for TransformStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{0:<3} {1:}", &self.transform, &self.chord)
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TonnetzPosition {
+    pub chord: String,
+    /// Fifths and major thirds above C of chord notes
+    pub coordinates: Vec<(i8, i8)>,
+    /// Major triads point up on the Tonnetz, minor triads down
+    pub up: bool,
+}
+
+impl fmt::Display for TonnetzPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<6} {1:<5} {2:}",
+            &self.chord,
+            if self.up { "up" } else { "down" },
+            &self
+                .coordinates
+                .iter()
+                .map(|(x, y)| format!("({}, {})", x, y))
+                .collect::<Vec<String>>()
+                .join(" ")
+        )
+    }
+}
+
+// Root and mode of major or minor triad
+fn triad(chord: &Chord) -> Option<((char, i8), bool)> {
+    let mut intervals = chord.intervals();
+    intervals.sort_unstable();
+    match intervals[..] {
+        [0, 4, 7] => Some((chord.root(), true)),
+        [0, 3, 7] => Some((chord.root(), false)),
+        _ => None,
+    }
+}
+
+// Triad spelled by letters, root spelled again when that would
+// take double accidentals (eg. E# major -> F major)
+fn build(root: (char, i8), major: bool) -> Chord {
+    let third = if major { 4 } else { 3 };
+    let letter = |n: (char, i8), letters: u8| (n.0 as u8 - b'a' + letters) % 7;
+    let root = if [(third, 2), (7, 4)].iter().all(|&(semitones, letters)| {
+        letter(util::spell_interval(root, semitones, letters), 0) == letter(root, letters)
+    }) {
+        root
+    } else {
+        util::alt_note(root)
+    };
+
+    let spelling = [
+        root,
+        util::spell_interval(root, third, 2),
+        util::spell_interval(root, 7, 4),
+    ];
+    Chord::from_intervals(root, vec![0, third, 7], &spelling)
+}
+
+/// Applies transform to major or minor triad, spelling the new root
+/// by letter from the old one. Other chords give None.
+pub fn apply(chord: &Chord, transform: Transform) -> Option<Chord> {
+    let (root, major) = triad(chord)?;
+    let (semitones, letters) = transform.root_motion()[if major { 0 } else { 1 }];
+    Some(build(
+        util::spell_interval(root, semitones, letters),
+        !major,
+    ))
+}
+
+/// Applies transforms in order, stopping at chords that aren't
+/// major or minor triads
+pub fn apply_all(chord: &Chord, transforms: &[Transform]) -> Vec<TransformStep> {
+    let mut result: Vec<TransformStep> = vec![];
+
+    for &t in transforms {
+        let current = result.last().map(|s| &s.chord).unwrap_or(chord);
+        match apply(current, t) {
+            Some(next) => result.push(TransformStep {
+                transform: String::from(t),
+                chord: next,
+            }),
+            None => break,
+        }
+    }
+
+    result
+}
+
+/// Returns the shortest chain of transforms from one triad to another,
+/// using P, L and R, or all transforms when compound is set.
+/// Ties go to the transform listed first.
+pub fn path(from: &Chord, to: &Chord, compound: bool) -> Option<Vec<TransformStep>> {
+    let key = |c: &Chord| triad(c).map(|(root, major)| (util::pitch_class(root), major));
+    let target = key(to)?;
+    let transforms = if compound {
+        &TRANSFORMS[..]
+    } else {
+        &TRANSFORMS[..3]
+    };
+
+    // breadth first over the 24 triads
+    let mut seen = vec![key(from)?];
+    let mut queue = VecDeque::from([(from.clone(), vec![])]);
+
+    while let Some((chord, steps)) = queue.pop_front() {
+        if key(&chord) == Some(target) {
+            return Some(steps);
+        }
+
+        for &t in transforms {
+            if let Some(next) = apply(&chord, t) {
+                let k = key(&next)?;
+                if !seen.contains(&k) {
+                    seen.push(k);
+                    let mut steps = steps.clone();
+                    steps.push(TransformStep {
+                        transform: String::from(t),
+                        chord: next.clone(),
+                    });
+                    queue.push_back((next, steps));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Position of pitch class as fifths (0 to 3) and major thirds (0 to 2)
+// above C
+fn coordinates(pc: u8) -> (i8, i8) {
+    let x = (pc * 3) % 4;
+    let y = ((pc + 12 * 7 - 7 * x) / 4) % 3;
+    (x as i8, y as i8)
+}
+
+/// Returns Tonnetz coordinates of major or minor triad, the root lying
+/// within three fifths and two major thirds above C
+pub fn tonnetz(chord: &Chord) -> Option<TonnetzPosition> {
+    let (root, major) = triad(chord)?;
+    let (x, y) = coordinates(util::pitch_class(root));
+
+    let third = if major { (x, y + 1) } else { (x + 1, y - 1) };
+    Some(TonnetzPosition {
+        chord: chord.name.clone(),
+        coordinates: vec![(x, y), third, (x + 1, y)],
+        up: major,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(steps: &[TransformStep]) -> Vec<String> {
+        steps.iter().map(|s| s.chord.name.clone()).collect()
+    }
+
+    #[test]
+    fn applies_transforms_in_order() {
+        let c = Chord::from_intervals(('c', 0), vec![0, 4, 7], &[]);
+        let steps = apply_all(&c, &[Transform::P, Transform::L, Transform::R]);
+        assert_eq!(names(&steps), vec!["Cm", "A♭", "Fm"]);
+    }
+
+    #[test]
+    fn shortest_path_between_triads() {
+        let c = Chord::from_intervals(('c', 0), vec![0, 4, 7], &[]);
+        let am = Chord::from_intervals(('a', 0), vec![0, 3, 7], &[]);
+        let e = Chord::from_intervals(('e', 0), vec![0, 4, 7], &[]);
+
+        assert_eq!(names(&path(&c, &am, false).unwrap()), vec!["Am"]);
+        assert_eq!(path(&c, &e, false).unwrap().len(), 2);
+        assert!(path(&c, &c, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn tonnetz_of_major_and_minor_triads() {
+        let c = Chord::from_intervals(('c', 0), vec![0, 4, 7], &[]);
+        let position = tonnetz(&c).unwrap();
+        assert!(position.up);
+        assert_eq!(position.coordinates, vec![(0, 0), (0, 1), (1, 0)]);
+
+        let cm = apply(&c, Transform::P).unwrap();
+        assert!(!tonnetz(&cm).unwrap().up);
+    }
+
+    #[test]
+    fn rejects_unknown_transforms() {
+        assert!(Transform::try_from("X".to_string()).is_err());
+        assert!(Transform::try_from("p".to_string()) == Ok(Transform::P));
+    }
+}
@@ -14,9 +14,9 @@ pub enum Note {
     Gs,
 }
 
-impl Into<String> for Note {
-    fn into(self) -> String {
-        match self {
+impl From<Note> for String {
+    fn from(note: Note) -> Self {
+        match note {
             Note::A => "A",
             Note::As => "A#",
             Note::B => "B",
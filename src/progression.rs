@@ -0,0 +1,594 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::scale;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+#[derive(PartialEq, Clone, Copy)]
+enum Quality {
+    Major,
+    Minor,
+    Diminished,
+    HalfDiminished,
+    Augmented,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ProgressionChord {
+    /// Numeral as entered (eg. "V6/5", "V/V" or "5/7")
+    pub numeral: String,
+    pub chord: Chord,
+    /// Bass note of inverted or slash chord
+    pub bass: Option<String>,
+    /// Length in beats
+    pub beats: f32,
+}
+
+impl ProgressionChord {
+    /// Returns chord symbol in given naming style, with slash bass.
+    /// Roman numerals of inverted triads and seventh chords take
+    /// figures instead (eg. "V6/5").
+    pub fn symbol(&self, style: Style, key: &[(char, i8)]) -> String {
+        let name = self.chord.styled_name(style, key);
+        let bass = match &self.bass {
+            Some(bass) => bass,
+            None => return name,
+        };
+
+        if style == Style::Roman {
+            if let Some(figured) = self.figured(&name, bass) {
+                return figured;
+            }
+        }
+        format!("{}/{}", name, bass.to_uppercase())
+    }
+
+    // Roman numeral with figures of the chord tone in bass,
+    // None for other chords and basses
+    fn figured(&self, roman: &str, bass: &str) -> Option<String> {
+        let root = util::pitch_class(self.chord.root());
+        let bass = (util::pitch_class(util::str_to_note(bass)) + 12 - root) % 12;
+        let seventh = roman.ends_with('7');
+        let triad = !roman.ends_with(|c: char| c.is_ascii_digit() || c == ')');
+        if !self.chord.intervals().contains(&bass)
+            || !(seventh && self.chord.notes.len() == 4 || triad && self.chord.notes.len() == 3)
+        {
+            return None;
+        }
+
+        let figure = match (seventh, bass) {
+            (_, 0) => return Some(roman.to_string()),
+            (false, 3) | (false, 4) => "6",
+            (false, _) => "6/4",
+            (true, 3) | (true, 4) => "6/5",
+            (true, 6) | (true, 7) | (true, 8) => "4/3",
+            (true, _) => "4/2",
+        };
+        Some(format!("{}{}", roman.trim_end_matches('7'), figure))
+    }
+}
+
+impl fmt::Display for ProgressionChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<10} {1:<16} ({2:})  {3:} beats",
+            &self.numeral,
+            self.symbol(Style::Default, &[]),
+            &self
+                .chord
+                .notes
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.beats
+        )
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Progression {
+    pub key: String,
+    pub scale: String,
+    pub chords: Vec<ProgressionChord>,
+}
+
+impl fmt::Display for Progression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chord in &self.chords {
+            writeln!(f, "{}", chord)?;
+        }
+        Ok(())
+    }
+}
+
+impl Progression {
+    /// Parses progression of Roman numerals (eg. "ii7 V7 Imaj7")
+    /// or Nashville numbers (eg. "1 6m 4 5/7") in key and scale.
+    /// Numerals are relative to the scale degrees, major for scales
+    /// other than heptatonic. Length in beats follows colon (eg. "V7:2"),
+    /// defaulting to a 4 beat bar.
+    pub fn parse(input: &str, key: &str, scale: &str) -> Result<Progression, String> {
+        let notes = degree_notes(util::str_to_note(key), scale);
+
+        Ok(Progression {
+            key: key.to_string(),
+            scale: scale.to_string(),
+            chords: input
+                .split_whitespace()
+                .map(|token| parse_token(token, |n| parse_numeral(n, &notes)))
+                .collect::<Result<Vec<ProgressionChord>, String>>()?,
+        })
+    }
+
+    /// Parses progression of chord symbols (eg. "Dm7 G7:2 C/E:2")
+    /// in key and scale, with lengths in beats as for numerals
+    pub fn from_symbols(input: &str, key: &str, scale: &str) -> Result<Progression, String> {
+        Ok(Progression {
+            key: key.to_string(),
+            scale: scale.to_string(),
+            chords: input
+                .split_whitespace()
+                .map(|token| parse_token(token, parse_symbol))
+                .collect::<Result<Vec<ProgressionChord>, String>>()?,
+        })
+    }
+
+    /// Returns the progression moved to another key.
+    /// Numerals are read again in the key, chord symbols are transposed.
+    pub fn in_key(&self, key: &str) -> Progression {
+        let notes = degree_notes(util::str_to_note(key), &self.scale);
+        let from = util::str_to_note(&self.key);
+        let to = util::str_to_note(key);
+        let semitones = (util::pitch_class(to) + 12 - util::pitch_class(from)) % 12;
+        let letters = (to.0 as u8 + 7 - from.0 as u8) % 7;
+        let transpose = |n: &str| {
+            util::note_to_str(util::spell_interval(
+                util::str_to_note(n),
+                semitones,
+                letters,
+            ))
+        };
+
+        Progression {
+            key: key.to_string(),
+            scale: self.scale.clone(),
+            chords: self
+                .chords
+                .iter()
+                .map(|c| match parse_numeral(&c.numeral, &notes) {
+                    Ok(parsed) => ProgressionChord {
+                        beats: c.beats,
+                        ..parsed
+                    },
+                    Err(_) => {
+                        let spelling: Vec<(char, i8)> = c
+                            .chord
+                            .notes
+                            .iter()
+                            .map(|n| util::str_to_note(&transpose(n)))
+                            .collect();
+                        let chord =
+                            Chord::from_intervals(spelling[0], c.chord.intervals(), &spelling);
+                        ProgressionChord {
+                            numeral: c.numeral.clone(),
+                            chord,
+                            bass: c.bass.as_ref().map(|b| transpose(b)),
+                            beats: c.beats,
+                        }
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns key notes, tonic first, as used for the numerals
+    pub fn key_notes(&self) -> Vec<(char, i8)> {
+        degree_notes(util::str_to_note(&self.key), &self.scale)
+    }
+
+    /// Returns chord symbols in given naming style
+    pub fn symbols(&self, style: Style) -> Vec<String> {
+        let key = self.key_notes();
+        self.chords.iter().map(|c| c.symbol(style, &key)).collect()
+    }
+
+    /// Returns total length in beats
+    pub fn beats(&self) -> f32 {
+        self.chords.iter().map(|c| c.beats).sum()
+    }
+}
+
+// Notes of scale degrees on tonic, spelled with consecutive letters
+pub(crate) fn degree_notes(tonic: (char, i8), scale: &str) -> Vec<(char, i8)> {
+    let mut steps: Vec<u8> = scale::get_scale(scale)
+        .into_iter()
+        .filter(|&s| s < 12)
+        .collect();
+    if steps.len() != 7 {
+        steps = scale::get_scale("major");
+    }
+
+    steps
+        .iter()
+        .take(7)
+        .enumerate()
+        .map(|(i, &s)| util::spell_interval(tonic, s, i as u8))
+        .collect()
+}
+
+// Notes of scale degrees as degree_notes, failing for scales
+// that don't have seven notes instead of falling back to major
+pub(crate) fn heptatonic_notes(tonic: (char, i8), scale: &str) -> Result<Vec<(char, i8)>, String> {
+    if scale::get_scale(scale).iter().filter(|&&s| s < 12).count() != 7 {
+        return Err(format!("Scale has no seven degrees: {}", scale));
+    }
+    Ok(degree_notes(tonic, scale))
+}
+
+// Splits leading accidental off (eg. "bVII" -> -1, "VII")
+fn accidental(s: &str) -> (i8, &str) {
+    for (prefix, alt) in [("b", -1), ("♭", -1), ("#", 1), ("♯", 1)].iter() {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            return (*alt, rest);
+        }
+    }
+    (0, s)
+}
+
+// Splits Roman numeral off, with whether it is uppercase
+fn roman(s: &str) -> Option<(usize, bool, &str)> {
+    for (degree, numeral) in NUMERALS.iter().enumerate().rev() {
+        if let Some(rest) = s.strip_prefix(numeral) {
+            return Some((degree, true, rest));
+        }
+        if let Some(rest) = s.strip_prefix(&numeral.to_lowercase()[..]) {
+            return Some((degree, false, rest));
+        }
+    }
+    None
+}
+
+// Splits Nashville number off (eg. "6m7" -> 5, "m7")
+fn nashville(s: &str) -> Option<(usize, &str)> {
+    let c = s.chars().next()?;
+    match c.to_digit(10) {
+        Some(d) if (1..=7).contains(&d) => Some((d as usize - 1, &s[1..])),
+        _ => None,
+    }
+}
+
+// Splits quality modifier off
+fn quality(s: &str, default: Quality) -> (Quality, &str) {
+    let modifiers = [
+        ("°", Quality::Diminished),
+        ("dim", Quality::Diminished),
+        ("o", Quality::Diminished),
+        ("ø", Quality::HalfDiminished),
+        ("+", Quality::Augmented),
+        ("aug", Quality::Augmented),
+    ];
+
+    for (prefix, q) in modifiers.iter() {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            return (*q, rest);
+        }
+    }
+    (default, s)
+}
+
+// Note of scale degree with accidental
+fn degree_note(notes: &[(char, i8)], degree: usize, alt: i8) -> (char, i8) {
+    let note = notes[degree];
+    match note.1 + alt {
+        a if a.abs() <= 1 => (note.0, a),
+        _ => util::spell_pitch_class(
+            (util::pitch_class(note) as i8 + 12 + alt) as u8 % 12,
+            &[],
+            alt < 0,
+        ),
+    }
+}
+
+// Chord tones as semitones and letter names above root,
+// with inversion given by figures (eg. "6/5" -> 1).
+// Without figures 6 is an added sixth, as in chord symbols.
+fn chord_tones(q: Quality, ext: &str, figured: bool) -> Option<(Vec<(u8, u8)>, usize)> {
+    let ext = ext.replace('/', "");
+    let mut major7 = false;
+    let mut rest = &ext[..];
+    for prefix in ["maj", "Maj", "ma", "M", "Δ"].iter() {
+        if let Some(r) = rest.strip_prefix(prefix) {
+            major7 = true;
+            rest = r;
+            break;
+        }
+    }
+
+    let split = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (figure, tail) = rest.split_at(split);
+
+    let (seventh, inversion, extensions): (bool, usize, Vec<(u8, u8)>) = match figure {
+        "" => (major7 || q == Quality::HalfDiminished, 0, vec![]),
+        "6" if !figured => (major7, 0, vec![(9, 5)]),
+        "69" if !figured => (major7, 0, vec![(9, 5), (2, 1)]),
+        "2" if !figured => (false, 0, vec![(2, 1)]),
+        "6" if !major7 => (false, 1, vec![]),
+        "64" if figured && !major7 => (false, 2, vec![]),
+        "7" => (true, 0, vec![]),
+        "65" if figured => (true, 1, vec![]),
+        "43" if figured => (true, 2, vec![]),
+        "42" | "2" if figured && !major7 => (true, 3, vec![]),
+        "9" => (true, 0, vec![(2, 1)]),
+        "11" => (true, 0, vec![(2, 1), (5, 3)]),
+        "13" => (true, 0, vec![(2, 1), (9, 5)]),
+        _ => return None,
+    };
+
+    let mut tones: Vec<(u8, u8)> = match q {
+        Quality::Major => vec![(0, 0), (4, 2), (7, 4)],
+        Quality::Minor => vec![(0, 0), (3, 2), (7, 4)],
+        Quality::Diminished | Quality::HalfDiminished => vec![(0, 0), (3, 2), (6, 4)],
+        Quality::Augmented => vec![(0, 0), (4, 2), (8, 4)],
+    };
+
+    match tail {
+        "" => {}
+        "sus" | "sus4" => tones[1] = (5, 3),
+        "sus2" => tones[1] = (2, 1),
+        "b5" | "♭5" => tones[2] = (6, 4),
+        "add9" | "add2" => tones.push((2, 1)),
+        _ => return None,
+    }
+
+    if seventh {
+        tones.push(match (q, major7) {
+            (_, true) => (11, 6),
+            (Quality::Diminished, false) => (9, 6),
+            _ => (10, 6),
+        });
+    }
+    tones.extend(extensions);
+
+    Some((tones, inversion))
+}
+
+// Root and whether chord is minor-like, for tonicized keys
+fn target(token: &str, notes: &[(char, i8)]) -> Option<((char, i8), bool)> {
+    let (alt, rest) = accidental(token);
+    let (degree, upper, rest) = roman(rest)?;
+    let (q, _) = quality(rest, Quality::Major);
+
+    let minor = !upper || q == Quality::Diminished || q == Quality::HalfDiminished;
+    Some((degree_note(notes, degree, alt), minor))
+}
+
+// Parses single numeral, without duration
+fn parse_numeral(token: &str, notes: &[(char, i8)]) -> Result<ProgressionChord, String> {
+    let error = || format!("Unknown chord numeral: {}", token);
+
+    let mut parts: Vec<&str> = token.split('/').collect();
+    let (alt, rest) = accidental(parts[0]);
+
+    let (root, q, ext, bass) = if let Some((degree, rest)) = nashville(rest) {
+        let (q, ext) = match rest.strip_prefix(['m', '-']) {
+            Some(r) if !rest.starts_with("ma") => (Quality::Minor, r),
+            _ => quality(rest, Quality::Major),
+        };
+
+        let bass = match parts.get(1) {
+            Some(b) => {
+                let (alt, b) = accidental(b);
+                match nashville(b) {
+                    Some((d, "")) => Some(degree_note(notes, d, alt)),
+                    _ => return Err(error()),
+                }
+            }
+            None => None,
+        };
+        if parts.len() > 2 {
+            return Err(error());
+        }
+
+        (degree_note(notes, degree, alt), q, ext.to_string(), bass)
+    } else {
+        // figures like 6/5 are split by the secondary function slash
+        let mut figure = parts[0].to_string();
+        if parts.len() > 1
+            && figure.ends_with(|c: char| c.is_ascii_digit())
+            && parts[1].chars().all(|c| c.is_ascii_digit())
+        {
+            figure = format!("{}/{}", figure, parts.remove(1));
+        }
+
+        // tonicized key of secondary functions, innermost last
+        let mut key = notes.to_vec();
+        for part in parts[1..].iter().rev() {
+            let (tonic, minor) = target(part, &key).ok_or_else(error)?;
+            key = degree_notes(tonic, if minor { "minor" } else { "major" });
+        }
+
+        let (alt, rest) = accidental(&figure);
+        let (degree, upper, rest) = roman(rest).ok_or_else(error)?;
+        let default = if upper {
+            Quality::Major
+        } else {
+            Quality::Minor
+        };
+        let (q, ext) = quality(rest, default);
+
+        // diminished leading tone chords raise the subtonic of minor keys
+        let subtonic = (util::pitch_class(key[0]) + 12 - util::pitch_class(key[6])) % 12 == 2;
+        let leading = degree == 6
+            && alt == 0
+            && subtonic
+            && (q == Quality::Diminished || q == Quality::HalfDiminished);
+        let alt = if leading { 1 } else { alt };
+
+        (degree_note(&key, degree, alt), q, ext.to_string(), None)
+    };
+
+    build(token, root, q, &ext, bass, true).ok_or_else(error)
+}
+
+// Builds chord of root, quality and extension, spelled by letter names
+fn build(
+    token: &str,
+    root: (char, i8),
+    q: Quality,
+    ext: &str,
+    bass: Option<(char, i8)>,
+    figured: bool,
+) -> Option<ProgressionChord> {
+    let (tones, inversion) = chord_tones(q, ext, figured)?;
+    let intervals: Vec<u8> = tones.iter().map(|t| t.0).collect();
+    let spelling: Vec<(char, i8)> = tones
+        .iter()
+        .map(|&(semitones, letters)| util::spell_interval(root, semitones, letters))
+        .collect();
+
+    let chord = Chord::from_intervals(root, intervals, &spelling);
+    let bass = match bass {
+        Some(b) => Some(util::note_to_str(b)),
+        None if inversion > 0 => chord.notes.get(inversion).cloned(),
+        None => None,
+    };
+
+    Some(ProgressionChord {
+        numeral: token.to_string(),
+        chord,
+        bass,
+        beats: 4.0,
+    })
+}
+
+/// Parses chord symbol (eg. "Dm7", "B♭maj7/D", "F#ø7" or "C(M7)").
+/// Numeral of the result is the symbol as entered.
+pub fn parse_symbol(symbol: &str) -> Result<ProgressionChord, String> {
+    let error = || format!("Unknown chord symbol: {}", symbol);
+
+    let plain: String = symbol.chars().filter(|&c| c != '(' && c != ')').collect();
+    let (name, bass) = match plain.split_once('/') {
+        Some((name, bass)) => (name, Some(bass)),
+        None => (&plain[..], None),
+    };
+
+    let letter = name
+        .chars()
+        .next()
+        .map(|c| c.to_ascii_lowercase())
+        .filter(|c| ('a'..='g').contains(c))
+        .ok_or_else(error)?;
+    let (alt, rest) = accidental(&name[1..]);
+
+    let (q, ext) = match rest
+        .strip_prefix("min")
+        .or_else(|| rest.strip_prefix(['m', '-']))
+    {
+        Some(r) if !rest.starts_with("ma") => (Quality::Minor, r),
+        _ => quality(rest, Quality::Major),
+    };
+
+    let bass = match bass {
+        Some(b) => {
+            let note = util::str_to_note(b);
+            if !('a'..='g').contains(&note.0) || util::note_to_str(note).len() < b.len() {
+                return Err(error());
+            }
+            Some(note)
+        }
+        None => None,
+    };
+
+    build(symbol, (letter, alt), q, ext, bass, false).ok_or_else(error)
+}
+
+// Parses numeral or symbol with optional duration (eg. "V7:2")
+fn parse_token<F>(token: &str, parse: F) -> Result<ProgressionChord, String>
+where
+    F: Fn(&str) -> Result<ProgressionChord, String>,
+{
+    let (numeral, beats) = match token.split_once(':') {
+        Some((n, b)) => (
+            n,
+            b.parse::<f32>()
+                .ok()
+                .filter(|b| b.is_finite() && *b > 0.0)
+                .ok_or_else(|| format!("Invalid duration: {}", token))?,
+        ),
+        None => (token, 4.0),
+    };
+
+    Ok(ProgressionChord {
+        beats,
+        ..parse(numeral)?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(p: &Progression) -> Vec<String> {
+        p.chords.iter().map(|c| c.chord.name.clone()).collect()
+    }
+
+    #[test]
+    fn parses_numerals_with_lengths() {
+        let p = Progression::parse("ii7 V7:2 Imaj7", "C", "major").unwrap();
+        assert_eq!(names(&p), vec!["Dm(7)", "G(7)", "C(M7)"]);
+        assert_eq!(p.beats(), 10.0);
+    }
+
+    #[test]
+    fn parses_nashville_numbers_with_bass() {
+        let p = Progression::parse("1 6m 4 5/7", "G", "major").unwrap();
+        assert_eq!(p.chords[3].bass.as_deref(), Some("f#"));
+        assert!(Progression::parse("1 H7", "G", "major").is_err());
+    }
+
+    #[test]
+    fn roman_symbols_figure_inversions() {
+        let p = Progression::parse("I6 V6/4 V6/5 V4/3 V4/2 Imaj7", "C", "major").unwrap();
+        assert_eq!(
+            p.symbols(Style::Roman),
+            vec!["I6", "V6/4", "V6/5", "V4/3", "V4/2", "IM7"]
+        );
+        assert_eq!(
+            Progression::from_symbols("C/D", "C", "major")
+                .unwrap()
+                .symbols(Style::Roman),
+            vec!["I/D"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for input in ["I:0", "I:-2", "I:inf", "I:NaN", "I:x"] {
+            assert!(Progression::parse(input, "C", "major").is_err());
+        }
+    }
+
+    #[test]
+    fn parses_chord_symbols() {
+        assert_eq!(
+            parse_symbol("B♭m7").unwrap().chord.notes,
+            vec!["b♭", "d♭", "f", "a♭"]
+        );
+        assert_eq!(parse_symbol("G7/B").unwrap().bass.as_deref(), Some("b"));
+        assert!(parse_symbol("H7").is_err());
+    }
+
+    #[test]
+    fn moves_numerals_to_another_key() {
+        let p = Progression::parse("ii7 V7 I", "C", "major").unwrap();
+        assert_eq!(names(&p.in_key("F")), vec!["Gm(7)", "C(7)", "F"]);
+    }
+}
@@ -0,0 +1,499 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::Style;
+use crate::progression::{self, Progression, ProgressionChord};
+use crate::util;
+use crate::voiceleading::{self, VoiceLeadingOptions};
+use serde_derive::{Deserialize, Serialize};
+
+// Search costs of a repeated chord, a diatonic chord under other
+// strategies and an ending away from tonic
+const REPEAT: i32 = 3;
+const DIATONIC: i32 = 1;
+const ENDING: i32 = 4;
+// Sequences kept at each melody note, with and without
+// chords of the strategy alike
+const BEAM: usize = 64;
+// Alternatives returned
+const ALTERNATIVES: usize = 5;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Strategy {
+    Diatonic,
+    Secondary,
+    Tritone,
+    Mediant,
+    Coltrane,
+}
+
+impl From<Strategy> for String {
+    fn from(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Diatonic => "diatonic",
+            Strategy::Secondary => "secondary",
+            Strategy::Tritone => "tritone",
+            Strategy::Mediant => "mediant",
+            Strategy::Coltrane => "coltrane",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Strategy {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "secondary" => Strategy::Secondary,
+            "tritone" => Strategy::Tritone,
+            "mediant" => Strategy::Mediant,
+            "coltrane" => Strategy::Coltrane,
+            _ => Strategy::Diatonic,
+        }
+    }
+}
+
+pub fn supported_strategies() -> Vec<String> {
+    ["diatonic", "secondary", "tritone", "mediant", "coltrane"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct MelodyNote {
+    pub note: String,
+    /// Length in quarter note beats
+    pub beats: f32,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Reharmonization {
+    pub strategy: String,
+    /// Chords lasting as long as the melody notes they harmonize
+    pub progression: Progression,
+    /// Total voice movement in semitones
+    pub cost: u16,
+}
+
+impl fmt::Display for Reharmonization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<10} {1:>3}  {2:}",
+            &self.strategy,
+            self.cost,
+            &self
+                .progression
+                .chords
+                .iter()
+                .map(|c| format!("{}:{}", c.chord.name, c.beats))
+                .collect::<Vec<String>>()
+                .join(" ")
+        )
+    }
+}
+
+/// Parses melody of notes with optional octave and length in quarter
+/// note beats (eg. "E5:2 D5 C5:0.5"), notes lasting a beat by default
+pub fn parse_melody(input: &str) -> Result<Vec<MelodyNote>, String> {
+    input
+        .split_whitespace()
+        .map(|token| {
+            let (note, beats) = match token.split_once(':') {
+                Some((n, b)) => (
+                    n,
+                    b.parse::<f32>()
+                        .ok()
+                        .filter(|b| b.is_finite() && *b > 0.0)
+                        .ok_or_else(|| format!("Invalid duration: {}", token))?,
+                ),
+                None => (token, 1.0),
+            };
+
+            let pitch = note.trim_end_matches(|c: char| c.is_ascii_digit());
+            let parsed = util::str_to_note(pitch);
+            if !('a'..='g').contains(&parsed.0) || util::note_to_str(parsed).len() < pitch.len() {
+                return Err(format!("Unknown melody note: {}", token));
+            }
+
+            Ok(MelodyNote {
+                note: util::note_to_str(parsed),
+                beats,
+            })
+        })
+        .collect()
+}
+
+/// Returns melody of progression as the top note of each chord
+/// in close position
+pub fn melody_of(progression: &Progression) -> Vec<MelodyNote> {
+    progression
+        .chords
+        .iter()
+        .filter_map(|c| {
+            c.chord.notes.last().map(|n| MelodyNote {
+                note: n.clone(),
+                beats: c.beats,
+            })
+        })
+        .collect()
+}
+
+// Chord the search may pick, with the root it has to resolve to
+// and whether it belongs to the strategy rather than the key
+struct Candidate {
+    chord: Chord,
+    resolves: Option<u8>,
+    mediant: bool,
+    outside: bool,
+}
+
+impl Candidate {
+    fn root(&self) -> u8 {
+        util::pitch_class(self.chord.root())
+    }
+}
+
+// Triads and seventh chords of scale degrees
+fn diatonic(notes: &[(char, i8)]) -> Vec<Chord> {
+    (0..7)
+        .flat_map(|degree| {
+            let root_pc = util::pitch_class(notes[degree]);
+            [3, 4].map(|voices| {
+                let intervals: Vec<u8> = (0..voices)
+                    .map(|i| (util::pitch_class(notes[(degree + i * 2) % 7]) + 12 - root_pc) % 12)
+                    .collect();
+                Chord::from_intervals(notes[degree], intervals, notes)
+            })
+        })
+        .collect()
+}
+
+fn candidates(notes: &[(char, i8)], strategy: Strategy) -> Vec<Candidate> {
+    let key: Vec<u8> = notes.iter().map(|&n| util::pitch_class(n)).collect();
+    let flats = !notes.iter().any(|n| n.1 > 0);
+    let tonic = key[0];
+
+    let chord = |root: u8, intervals: &[u8], flats: bool| {
+        let root = util::spell_pitch_class(root % 12, notes, flats);
+        Chord::from_intervals(root, intervals.to_vec(), notes)
+    };
+    let candidate = |chord: Chord, resolves: Option<u8>, mediant: bool| Candidate {
+        outside: !chord.pitch_classes().iter().all(|pc| key.contains(pc)),
+        chord,
+        resolves,
+        mediant,
+    };
+
+    let mut result: Vec<Candidate> = diatonic(notes)
+        .into_iter()
+        .map(|c| candidate(c, None, false))
+        .collect();
+
+    // degrees with major or minor triads, that can be tonicized
+    let targets: Vec<u8> = (0..7)
+        .filter(|&d| (key[(d + 4) % 7] + 12 - key[d]) % 12 == 7)
+        .map(|d| key[d])
+        .collect();
+
+    match strategy {
+        Strategy::Diatonic => {}
+        Strategy::Secondary => {
+            for &target in &targets {
+                for intervals in [&[0, 4, 7][..], &[0, 4, 7, 10]] {
+                    result.push(candidate(
+                        chord(target + 7, intervals, flats),
+                        Some(target),
+                        false,
+                    ));
+                }
+            }
+        }
+        Strategy::Tritone => {
+            for &target in &targets {
+                result.push(candidate(
+                    chord(target + 1, &[0, 4, 7, 10], true),
+                    Some(target),
+                    false,
+                ));
+            }
+        }
+        Strategy::Mediant => {
+            for root in 0..12 {
+                for intervals in [[0, 4, 7], [0, 3, 7]] {
+                    let c = candidate(chord(root, &intervals, flats), None, true);
+                    if c.outside {
+                        result.push(c);
+                    }
+                }
+            }
+        }
+        Strategy::Coltrane => {
+            // tonics a major third apart, each with its dominant
+            for center in [tonic, tonic + 8, tonic + 4] {
+                for intervals in [&[0, 4, 7][..], &[0, 4, 7, 11]] {
+                    result.push(candidate(chord(center, intervals, flats), None, false));
+                }
+                result.push(candidate(
+                    chord(center + 7, &[0, 4, 7, 10], flats),
+                    Some(center % 12),
+                    false,
+                ));
+            }
+        }
+    }
+
+    // no duplicates of diatonic chords
+    let mut unique: Vec<Candidate> = vec![];
+    for c in result {
+        let mut pcs = c.chord.pitch_classes();
+        pcs.sort_unstable();
+        let duplicate = unique.iter().any(|u| {
+            let mut other = u.chord.pitch_classes();
+            other.sort_unstable();
+            other == pcs && u.chord.root() == c.chord.root()
+        });
+        if !duplicate {
+            unique.push(c);
+        }
+    }
+    unique
+}
+
+// Semitones each tone of chord moves to the nearest tone of previous chord
+fn distance(from: &Chord, to: &Chord) -> i32 {
+    let from = from.pitch_classes();
+    to.pitch_classes()
+        .iter()
+        .map(|&b| {
+            from.iter()
+                .map(|&a| {
+                    let d = (a + 12 - b) % 12;
+                    d.min(12 - d) as i32
+                })
+                .min()
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+// Cost of moving between candidates, None when the move breaks
+// a resolution or a mediant relation
+fn step(from: &Candidate, to: &Candidate, strategy: Strategy) -> Option<i32> {
+    if from.resolves.is_some_and(|r| r != to.root()) {
+        return None;
+    }
+
+    if to.mediant && to.outside {
+        let interval = (to.root() + 12 - from.root()) % 12;
+        if ![3, 4, 8, 9].contains(&interval) || from.chord.intervals() != to.chord.intervals() {
+            return None;
+        }
+    }
+
+    let mut cost = distance(&from.chord, &to.chord);
+    if from.chord.pitch_classes() == to.chord.pitch_classes() {
+        cost += REPEAT;
+    }
+    if strategy != Strategy::Diatonic && !to.outside {
+        cost += DIATONIC;
+    }
+    Some(cost)
+}
+
+/// Proposes chords for each melody note in key and scale, every chord
+/// containing its note. Alternatives use diatonic chords along with
+/// chords of the strategy, applied dominants resolving to their target,
+/// and are ranked by voice leading cost. Repeated chords are merged.
+/// Scale has to have seven notes.
+pub fn reharmonize(
+    melody: &[MelodyNote],
+    key: &str,
+    scale: &str,
+    strategy: Strategy,
+    options: &VoiceLeadingOptions,
+) -> Result<Vec<Reharmonization>, String> {
+    let notes = progression::heptatonic_notes(util::str_to_note(key), scale)?;
+    let tonic = util::pitch_class(notes[0]);
+    let all = candidates(&notes, strategy);
+
+    let fitting: Vec<Vec<usize>> = melody
+        .iter()
+        .map(|m| {
+            let pc = util::pitch_class(util::str_to_note(&m.note));
+            (0..all.len())
+                .filter(|&i| all[i].chord.pitch_classes().contains(&pc))
+                .collect()
+        })
+        .collect();
+
+    // beam search over candidate sequences, flagged when they use
+    // a chord of the strategy
+    let mut beam: Vec<(i32, Vec<usize>, bool)> = vec![(0, vec![], false)];
+    for (n, fits) in fitting.iter().enumerate() {
+        let mut next = vec![];
+        for (score, path, outside) in &beam {
+            for &i in fits {
+                let cost = match path.last() {
+                    Some(&p) => match step(&all[p], &all[i], strategy) {
+                        Some(c) => c,
+                        None => continue,
+                    },
+                    None if all[i].mediant && all[i].outside => continue,
+                    None => 0,
+                };
+
+                let last = n == fitting.len() - 1;
+                if last && all[i].resolves.is_some() {
+                    continue;
+                }
+                let ending = if last && all[i].root() != tonic {
+                    ENDING
+                } else {
+                    0
+                };
+
+                let mut path = path.clone();
+                path.push(i);
+                next.push((score + cost + ending, path, *outside || all[i].outside));
+            }
+        }
+
+        // diatonic sequences are cheaper and would fill the beam
+        next.sort_by_key(|s| s.0);
+        let (mut inside, mut outside) = (0, 0);
+        next.retain(|s| {
+            let count = if s.2 { &mut outside } else { &mut inside };
+            *count += 1;
+            *count <= BEAM
+        });
+        beam = next;
+    }
+
+    let mut result: Vec<Reharmonization> = vec![];
+    for (_, path, outside) in beam {
+        if strategy != Strategy::Diatonic && !outside {
+            continue;
+        }
+
+        let mut chords: Vec<ProgressionChord> = vec![];
+        for (&i, m) in path.iter().zip(melody) {
+            match chords.last_mut() {
+                Some(last) if last.chord.notes == all[i].chord.notes => last.beats += m.beats,
+                _ => chords.push(ProgressionChord {
+                    numeral: all[i].chord.styled_name(Style::Roman, &notes),
+                    chord: all[i].chord.clone(),
+                    bass: None,
+                    beats: m.beats,
+                }),
+            }
+        }
+
+        if result.iter().any(|r| r.progression.chords == chords) {
+            continue;
+        }
+
+        let plain: Vec<Chord> = chords.iter().map(|c| c.chord.clone()).collect();
+        let cost = voiceleading::lead(&plain, options)
+            .iter()
+            .map(|l| l.distance as u16)
+            .sum();
+
+        result.push(Reharmonization {
+            strategy: String::from(strategy),
+            progression: Progression {
+                key: key.to_string(),
+                scale: scale.to_string(),
+                chords,
+            },
+            cost,
+        });
+
+        if result.len() == ALTERNATIVES {
+            break;
+        }
+    }
+
+    // stable sort keeps search order on ties
+    result.sort_by_key(|r| r.cost);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_melody_lengths() {
+        let melody = parse_melody("E5:2 D5 C5:0.5").unwrap();
+        let beats: Vec<f32> = melody.iter().map(|m| m.beats).collect();
+        assert_eq!(beats, vec![2.0, 1.0, 0.5]);
+        assert_eq!(melody[0].note, "e");
+        assert!(parse_melody("E5:x").is_err());
+        assert!(parse_melody("E:-1 D").is_err());
+        assert!(parse_melody("E:0").is_err());
+    }
+
+    #[test]
+    fn diatonic_chords_contain_melody_and_end_on_tonic() {
+        let melody = parse_melody("E D C").unwrap();
+        let options = VoiceLeadingOptions::default();
+        let result = reharmonize(&melody, "C", "major", Strategy::Diatonic, &options).unwrap();
+
+        assert!(!result.is_empty());
+        for r in &result {
+            let chords = &r.progression.chords;
+            assert_eq!(chords.len(), 3);
+            for (c, m) in chords.iter().zip(&melody) {
+                assert!(c.chord.notes.contains(&m.note));
+            }
+            assert_eq!(chords[2].chord.root(), ('c', 0));
+        }
+    }
+
+    #[test]
+    fn secondary_dominants_resolve() {
+        let melody = parse_melody("E D C").unwrap();
+        let options = VoiceLeadingOptions::default();
+        let best = &reharmonize(&melody, "C", "major", Strategy::Secondary, &options).unwrap()[0];
+        let names: Vec<&str> = best
+            .progression
+            .chords
+            .iter()
+            .map(|c| &c.chord.name[..])
+            .collect();
+        assert_eq!(names, vec!["Em(7)", "E(7)", "Am"]);
+    }
+
+    #[test]
+    fn tritone_substitution_under_three_notes() {
+        let melody = parse_melody("E D C").unwrap();
+        let result = reharmonize(
+            &melody,
+            "C",
+            "major",
+            Strategy::Tritone,
+            &VoiceLeadingOptions::default(),
+        )
+        .unwrap();
+        assert!(!result.is_empty());
+        assert!(result.iter().all(|r| {
+            let names: Vec<&str> = r
+                .progression
+                .chords
+                .iter()
+                .map(|c| &c.chord.name[..])
+                .collect();
+            names
+                .windows(2)
+                .any(|w| w[0] == "B♭(7)" && w[1].starts_with("Am"))
+        }));
+    }
+
+    #[test]
+    fn rejects_scales_without_seven_degrees() {
+        let melody = parse_melody("E D C").unwrap();
+        let options = VoiceLeadingOptions::default();
+        assert!(reharmonize(&melody, "C", "pentatonic", Strategy::Diatonic, &options).is_err());
+    }
+}
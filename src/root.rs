@@ -0,0 +1,221 @@
+use std::fmt;
+
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum RootMethod {
+    Hindemith,
+    Parncutt,
+    Terhardt,
+}
+
+impl From<RootMethod> for String {
+    fn from(method: RootMethod) -> Self {
+        match method {
+            RootMethod::Hindemith => "hindemith",
+            RootMethod::Parncutt => "parncutt",
+            RootMethod::Terhardt => "terhardt",
+        }
+        .into()
+    }
+}
+
+impl From<String> for RootMethod {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "hindemith" => RootMethod::Hindemith,
+            "terhardt" => RootMethod::Terhardt,
+            _ => RootMethod::Parncutt,
+        }
+    }
+}
+
+pub fn supported_methods() -> Vec<String> {
+    ["hindemith", "parncutt", "terhardt"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct RootCandidate {
+    pub note: String,
+    pub score: f32,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct RootAnalysis {
+    pub method: String,
+    pub root: String,
+    /// Candidates by descending score
+    pub candidates: Vec<RootCandidate>,
+}
+
+impl fmt::Display for RootAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<10} {1:<4} ({2:})",
+            &self.method,
+            &self.root,
+            &self
+                .candidates
+                .iter()
+                .map(|c| format!("{} {:.2}", c.note, c.score))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+// Hindemith's interval series 2: interval above lower note,
+// with its rank and whether the root is the upper note
+fn hindemith_interval(semitones: u8) -> Option<(u8, bool)> {
+    match semitones % 12 {
+        7 => Some((1, false)),
+        5 => Some((2, true)),
+        4 => Some((3, false)),
+        8 => Some((4, true)),
+        3 => Some((5, false)),
+        9 => Some((6, true)),
+        2 => Some((7, true)),
+        10 => Some((8, false)),
+        1 => Some((9, true)),
+        11 => Some((10, false)),
+        // tritone has no root
+        _ => None,
+    }
+}
+
+// Root of the best interval, ties going to the lower interval.
+// Notes are in voicing order, bass first.
+fn hindemith(pcs: &[u8]) -> Vec<(u8, f32)> {
+    let mut scores: Vec<(u8, f32)> = vec![];
+
+    for (i, &a) in pcs.iter().enumerate() {
+        for &b in pcs[i + 1..].iter() {
+            if let Some((rank, upper)) = hindemith_interval((b + 12 - a) % 12) {
+                let root = if upper { b } else { a };
+                // lower position in voicing breaks ties
+                let score = (11 - rank) as f32 - i as f32 * 0.01;
+
+                match scores.iter_mut().find(|(pc, _)| *pc == root) {
+                    Some(s) => s.1 = s.1.max(score),
+                    None => scores.push((root, score)),
+                }
+            }
+        }
+    }
+
+    scores
+}
+
+// Root support weights of intervals above root (Parncutt 1988)
+fn parncutt(pcs: &[u8]) -> Vec<(u8, f32)> {
+    (0..12)
+        .map(|r| {
+            let score = pcs
+                .iter()
+                .map(|&p| match (p + 12 - r) % 12 {
+                    0 => 10.0,
+                    7 => 5.0,
+                    4 => 3.0,
+                    10 => 2.0,
+                    2 => 1.0,
+                    _ => 0.0,
+                })
+                .sum();
+            (r, score)
+        })
+        .collect()
+}
+
+// Pitch classes of first harmonics above fundamental
+const HARMONICS: [u8; 10] = [0, 0, 7, 0, 4, 7, 10, 0, 2, 4];
+
+// Subharmonic matching: each note votes for the fundamentals
+// it is a low harmonic of, weighted by 1/harmonic number
+fn terhardt(pcs: &[u8]) -> Vec<(u8, f32)> {
+    (0..12)
+        .map(|r| {
+            let score = pcs
+                .iter()
+                .map(|&p| {
+                    HARMONICS
+                        .iter()
+                        .position(|&h| h == (p + 12 - r) % 12)
+                        .map_or(0.0, |k| 1.0 / (k + 1) as f32)
+                })
+                .sum();
+            (r, score)
+        })
+        .collect()
+}
+
+/// Finds perceived root of notes, given bass first.
+/// The root can be outside of notes for virtual pitch methods.
+pub fn find_root(notes: &[(char, i8)], method: RootMethod) -> RootAnalysis {
+    let pcs: Vec<u8> = notes.iter().map(|&n| util::pitch_class(n)).collect();
+    let flats = notes.iter().any(|n| n.1 < 0);
+
+    let mut scores = match method {
+        RootMethod::Hindemith => hindemith(&pcs),
+        RootMethod::Parncutt => parncutt(&pcs),
+        RootMethod::Terhardt => terhardt(&pcs),
+    };
+
+    scores.retain(|&(_, s)| s > 0.0);
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let candidates: Vec<RootCandidate> = scores
+        .into_iter()
+        .map(|(pc, score)| RootCandidate {
+            note: util::note_to_str(util::spell_pitch_class(pc, notes, flats)).to_uppercase(),
+            score,
+        })
+        .collect();
+
+    RootAnalysis {
+        method: method.into(),
+        root: candidates
+            .first()
+            .map(|c| c.note.clone())
+            .unwrap_or_default(),
+        candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(notes: &[(char, i8)], method: RootMethod) -> String {
+        find_root(notes, method).root
+    }
+
+    #[test]
+    fn hindemith_root_of_dominant_seventh() {
+        let c7 = [('c', 0), ('e', 0), ('g', 0), ('b', -1)];
+        assert_eq!(root(&c7, RootMethod::Hindemith), "C");
+    }
+
+    #[test]
+    fn hindemith_root_of_inverted_triad() {
+        // the fourth G-C has its root on top
+        let c64 = [('g', 0), ('c', 0), ('e', 0)];
+        assert_eq!(root(&c64, RootMethod::Hindemith), "C");
+    }
+
+    #[test]
+    fn parncutt_virtual_root_of_inverted_triad() {
+        let c6 = [('e', 0), ('g', 0), ('c', 0)];
+        assert_eq!(root(&c6, RootMethod::Parncutt), "C");
+    }
+
+    #[test]
+    fn terhardt_virtual_root_of_inverted_triad() {
+        let c64 = [('g', 0), ('c', 0), ('e', 0)];
+        assert_eq!(root(&c64, RootMethod::Terhardt), "C");
+    }
+}
@@ -15,12 +15,25 @@ pub enum Scale {
     Augmented,
     Wholetone,
     Pentatonic,
+    Messiaen1,
+    Messiaen2,
+    Messiaen3,
+    Messiaen4,
+    Messiaen5,
+    Messiaen6,
+    Messiaen7,
+    Melodicminorclassical,
+    Khamaj,
+    Yaman,
+    Desh,
+    Jaunpuri,
+    Bageshri,
     Chromatic,
 }
 
-impl Into<String> for Scale {
-    fn into(self) -> String {
-        match self {
+impl From<Scale> for String {
+    fn from(scale: Scale) -> Self {
+        match scale {
             Scale::Major => "major",
             Scale::Minor => "minor",
             Scale::Harmonicminor => "harmonicminor",
@@ -34,6 +47,19 @@ impl Into<String> for Scale {
             Scale::Augmented => "augmented",
             Scale::Wholetone => "wholetone",
             Scale::Pentatonic => "pentatonic",
+            Scale::Messiaen1 => "messiaen1",
+            Scale::Messiaen2 => "messiaen2",
+            Scale::Messiaen3 => "messiaen3",
+            Scale::Messiaen4 => "messiaen4",
+            Scale::Messiaen5 => "messiaen5",
+            Scale::Messiaen6 => "messiaen6",
+            Scale::Messiaen7 => "messiaen7",
+            Scale::Melodicminorclassical => "melodicminorclassical",
+            Scale::Khamaj => "khamaj",
+            Scale::Yaman => "yaman",
+            Scale::Desh => "desh",
+            Scale::Jaunpuri => "jaunpuri",
+            Scale::Bageshri => "bageshri",
             Scale::Chromatic => "chromatic",
         }
         .into()
@@ -56,6 +82,19 @@ impl From<String> for Scale {
             "augmented" => Scale::Augmented,
             "wholetone" => Scale::Wholetone,
             "pentatonic" => Scale::Pentatonic,
+            "messiaen1" => Scale::Messiaen1,
+            "messiaen2" => Scale::Messiaen2,
+            "messiaen3" => Scale::Messiaen3,
+            "messiaen4" => Scale::Messiaen4,
+            "messiaen5" => Scale::Messiaen5,
+            "messiaen6" => Scale::Messiaen6,
+            "messiaen7" => Scale::Messiaen7,
+            "melodicminorclassical" => Scale::Melodicminorclassical,
+            "khamaj" => Scale::Khamaj,
+            "yaman" => Scale::Yaman,
+            "desh" => Scale::Desh,
+            "jaunpuri" => Scale::Jaunpuri,
+            "bageshri" => Scale::Bageshri,
             _ => Scale::Chromatic,
         }
     }
@@ -63,32 +102,32 @@ impl From<String> for Scale {
 
 // Returns all notes on chromatic scale
 pub fn chromatic_notes(root: (char, i8)) -> Vec<(char, i8)> {
-    let mut v = vec![];
-
-    // a
-    v.push(('a', 0));
-    // a sharp
-    v.push(('a', 1));
-    // b
-    v.push(('b', 0));
-    // c
-    v.push(('c', 0));
-    // c sharp
-    v.push(('c', 1));
-    // d
-    v.push(('d', 0));
-    // d sharp
-    v.push(('d', 1));
-    // e
-    v.push(('e', 0));
-    // f
-    v.push(('f', 0));
-    // f sharp
-    v.push(('f', 1));
-    // g
-    v.push(('g', 0));
-    // g sharp
-    v.push(('g', 1));
+    let v = vec![
+        // a
+        ('a', 0),
+        // a sharp
+        ('a', 1),
+        // b
+        ('b', 0),
+        // c
+        ('c', 0),
+        // c sharp
+        ('c', 1),
+        // d
+        ('d', 0),
+        // d sharp
+        ('d', 1),
+        // e
+        ('e', 0),
+        // f
+        ('f', 0),
+        // f sharp
+        ('f', 1),
+        // g
+        ('g', 0),
+        // g sharp
+        ('g', 1),
+    ];
 
     // Split and join according to root note
     let root_index = v
@@ -129,13 +168,27 @@ pub fn supported_scales() -> Vec<String> {
         "augmented",
         "wholetone",
         "pentatonic",
+        "messiaen1",
+        "messiaen2",
+        "messiaen3",
+        "messiaen4",
+        "messiaen5",
+        "messiaen6",
+        "messiaen7",
+        "melodicminorclassical",
+        "khamaj",
+        "yaman",
+        "desh",
+        "jaunpuri",
+        "bageshri",
     ]
     .iter()
     .map(|s| s.to_string())
     .collect()
 }
 
-/// Returns scales by name
+/// Returns scales by name, in ascending form
+/// (aroha of ragas) for scales that differ going down
 pub fn get_scale(scale: &str) -> Vec<u8> {
     vec_sum(match scale {
         "major" | "ionian" => vec![2, 2, 1, 2, 2, 2, 1],
@@ -151,11 +204,46 @@ pub fn get_scale(scale: &str) -> Vec<u8> {
         "augmented" => vec![1, 3, 1, 3, 1],
         "wholetone" => vec![2, 2, 2, 2, 2, 2],
         "pentatonic" => vec![2, 2, 3, 2],
+        // Messiaen's modes of limited transposition
+        "messiaen1" => vec![2, 2, 2, 2, 2, 2],
+        "messiaen2" | "octatonic" => vec![1, 2, 1, 2, 1, 2, 1, 2],
+        "messiaen3" => vec![2, 1, 1, 2, 1, 1, 2, 1, 1],
+        "messiaen4" => vec![1, 1, 3, 1, 1, 1, 3, 1],
+        "messiaen5" => vec![1, 4, 1, 1, 4, 1],
+        "messiaen6" => vec![2, 2, 1, 1, 2, 2, 1, 1],
+        "messiaen7" => vec![1, 1, 1, 2, 1, 1, 1, 1, 2, 1],
+        "melodicminorclassical" => vec![2, 1, 2, 2, 2, 2, 1],
+        // ragas by aroha
+        "khamaj" => vec![4, 1, 2, 2, 2, 1],
+        "yaman" => vec![2, 2, 2, 3, 2, 1],
+        "desh" => vec![2, 3, 2, 4, 1],
+        "jaunpuri" => vec![2, 3, 2, 1, 2, 2],
+        "bageshri" => vec![3, 2, 4, 1, 2],
         "chromatic" => vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
         _ => parse_scale(scale),
     })
 }
 
+/// Returns descending form of scale (avaroha of ragas) as semitones
+/// above root from the bottom up, same as get_scale for scales going
+/// down the way they go up
+pub fn get_descending_scale(scale: &str) -> Vec<u8> {
+    match scale {
+        "melodicminorclassical" => vec_sum(vec![2, 1, 2, 2, 1, 2, 2]),
+        "khamaj" | "desh" => vec_sum(vec![2, 2, 1, 2, 2, 1, 2]),
+        "yaman" => vec_sum(vec![2, 2, 2, 1, 2, 2, 1]),
+        "jaunpuri" => vec_sum(vec![2, 1, 2, 2, 1, 2, 2]),
+        "bageshri" => vec_sum(vec![2, 1, 2, 2, 2, 1, 2]),
+        _ => get_scale(scale),
+    }
+}
+
+/// Whether scale has other notes going down than going up
+pub fn is_directional(scale: &str) -> bool {
+    let pcs = |v: Vec<u8>| v.into_iter().filter(|&i| i < 12).collect::<Vec<u8>>();
+    pcs(get_scale(scale)) != pcs(get_descending_scale(scale))
+}
+
 // Parses scale from "1,1,..." format
 fn parse_scale(scalestr: &str) -> Vec<u8> {
     scalestr
@@ -168,7 +256,7 @@ fn parse_scale(scalestr: &str) -> Vec<u8> {
 fn vec_sum(interval: Vec<u8>) -> Vec<u8> {
     let mut vec: Vec<u8> = vec![0];
     vec.extend(interval.into_iter().scan(0, |sum, step| {
-        *sum = *sum + step;
+        *sum += step;
         Some(*sum)
     }));
     vec
@@ -184,6 +272,44 @@ pub fn friendly_name(name: &str) -> String {
         "melodicminor" => "Melodic minor",
         "overtone" => "Overtone",
         "chromatic" => "Chromatic",
+        "melodicminorclassical" => "Classical melodic minor",
+        "khamaj" => "Raga Khamaj",
+        "yaman" => "Raga Yaman",
+        "desh" => "Raga Desh",
+        "jaunpuri" => "Raga Jaunpuri",
+        "bageshri" => "Raga Bageshri",
+        "messiaen1" => "Messiaen mode 1",
+        "messiaen2" | "octatonic" => "Messiaen mode 2",
+        "messiaen3" => "Messiaen mode 3",
+        "messiaen4" => "Messiaen mode 4",
+        "messiaen5" => "Messiaen mode 5",
+        "messiaen6" => "Messiaen mode 6",
+        "messiaen7" => "Messiaen mode 7",
         _ => name,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn melodic_minor_descends_as_natural_minor() {
+        assert_eq!(
+            get_scale("melodicminorclassical")[..7],
+            [0, 2, 3, 5, 7, 9, 11]
+        );
+        assert_eq!(
+            get_descending_scale("melodicminorclassical")[..7],
+            [0, 2, 3, 5, 7, 8, 10]
+        );
+    }
+
+    #[test]
+    fn directional_scales() {
+        assert!(is_directional("melodicminorclassical"));
+        assert!(is_directional("khamaj"));
+        assert!(!is_directional("melodicminor"));
+        assert!(!is_directional("major"));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+/// Ranks chords found in a scale.
+/// Levels are the scale degree indexes of chord notes above root
+/// (eg. 2, 4 for a triad), lower weight being the more common chord.
+pub trait ChordScorer {
+    fn weight(&self, levels: &[u8]) -> i8;
+
+    /// Whether chord of given weight is listed as extended
+    fn is_extended(&self, weight: i8) -> bool;
+}
+
+/// Default heuristic of the library
+pub struct DefaultScorer;
+
+impl ChordScorer for DefaultScorer {
+    fn weight(&self, levels: &[u8]) -> i8 {
+        util::weight_levels(levels)
+    }
+
+    fn is_extended(&self, weight: i8) -> bool {
+        weight > 4
+    }
+}
+
+/// Data-driven scorer, with the default heuristic as default values.
+/// Can be loaded from json with `ScoringProfile::from_json`.
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct ScoringProfile {
+    /// Weight by number of notes above root, starting from 2 notes.
+    /// Last value applies to all longer chords.
+    pub length: Vec<i8>,
+    /// Weight of each included level, starting from level 1.
+    /// Last value applies to all higher levels.
+    pub included: Vec<i8>,
+    /// Weight of each missing level, starting from level 1
+    pub missing: Vec<i8>,
+    /// Chords weighing more than this are extended
+    pub extended_threshold: i8,
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        ScoringProfile {
+            length: vec![0, 2, 4, 6, 8],
+            included: vec![4, -2, 5, -3, 4, 2, 9],
+            missing: vec![0, 4, 0, 4, 0, 0],
+            extended_threshold: 4,
+        }
+    }
+}
+
+impl ScoringProfile {
+    pub fn from_json(s: &str) -> Result<ScoringProfile, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+}
+
+// Returns value at index, or last value past the end
+fn lookup(table: &[i8], index: usize) -> i8 {
+    table
+        .get(index)
+        .or_else(|| table.last())
+        .copied()
+        .unwrap_or(0)
+}
+
+impl ChordScorer for ScoringProfile {
+    fn weight(&self, levels: &[u8]) -> i8 {
+        // summed wide, as profile weights can be anything i8 holds
+        let mut result = lookup(&self.length, levels.len().saturating_sub(2)) as i32;
+
+        for &v in levels.iter().filter(|&&v| v > 0) {
+            result += lookup(&self.included, v as usize - 1) as i32;
+        }
+
+        for (i, &w) in self.missing.iter().enumerate() {
+            if !levels.contains(&(i as u8 + 1)) {
+                result += w as i32;
+            }
+        }
+
+        result.clamp(i8::MIN as i32, i8::MAX as i32) as i8
+    }
+
+    fn is_extended(&self, weight: i8) -> bool {
+        weight > self.extended_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_matches_default_scorer() {
+        let profile = ScoringProfile::default();
+        for levels in [&[2, 4][..], &[2, 4, 6], &[1, 4], &[2, 4, 6, 8, 10]] {
+            assert_eq!(profile.weight(levels), DefaultScorer.weight(levels));
+        }
+    }
+
+    #[test]
+    fn partial_profile_keeps_defaults() {
+        let profile = ScoringProfile::from_json(r#"{"extended_threshold": 10}"#).unwrap();
+        assert_eq!(profile.length, ScoringProfile::default().length);
+        assert!(!profile.is_extended(10));
+        assert!(profile.is_extended(11));
+    }
+
+    #[test]
+    fn large_weights_saturate() {
+        let profile = ScoringProfile::from_json(r#"{"included": [127], "length": [127]}"#).unwrap();
+        assert_eq!(profile.weight(&[2, 4, 6]), i8::MAX);
+    }
+}
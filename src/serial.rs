@@ -0,0 +1,298 @@
+use std::fmt;
+
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Form {
+    Prime,
+    Retrograde,
+    Inversion,
+    RetrogradeInversion,
+}
+
+impl From<Form> for String {
+    fn from(form: Form) -> Self {
+        match form {
+            Form::Prime => "P",
+            Form::Retrograde => "R",
+            Form::Inversion => "I",
+            Form::RetrogradeInversion => "RI",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Form {
+    fn from(s: String) -> Self {
+        match &s.to_lowercase()[..] {
+            "r" | "retrograde" => Form::Retrograde,
+            "i" | "inversion" => Form::Inversion,
+            "ri" | "retrogradeinversion" => Form::RetrogradeInversion,
+            _ => Form::Prime,
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct RowMatrix {
+    /// Row as entered, spelling every pitch class of the matrix
+    pub row: Vec<String>,
+    /// Prime forms from top to bottom, read backwards for retrogrades
+    pub matrix: Vec<Vec<String>>,
+    /// Labels of rows (eg. "P0") and columns (eg. "I0"), transpositions
+    /// counted from the first note of the row
+    pub primes: Vec<String>,
+    pub inversions: Vec<String>,
+    /// Forms whose first hexachord complements the one of P0
+    /// (eg. "I5" or "RI11")
+    pub combinatorial: Vec<String>,
+}
+
+impl fmt::Display for RowMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = |v: &[String]| {
+            v.iter()
+                .map(|s| format!("{:<5}", s))
+                .collect::<Vec<String>>()
+                .join("")
+        };
+
+        writeln!(f, "{0:<5}{1:}", "", cells(&self.inversions))?;
+        for (label, row) in self.primes.iter().zip(&self.matrix) {
+            let notes: Vec<String> = row.iter().map(|n| n.to_uppercase()).collect();
+            writeln!(f, "{0:<5}{1:}R{2:}", label, cells(&notes), &label[1..])?;
+        }
+        let retrogrades: Vec<String> = self.inversions.iter().map(|i| format!("R{}", i)).collect();
+        writeln!(f, "{0:<5}{1:}", "", cells(&retrogrades))?;
+
+        if self.combinatorial.is_empty() {
+            write!(f, "Not hexachordally combinatorial")
+        } else {
+            write!(
+                f,
+                "Hexachordally combinatorial with {}",
+                self.combinatorial.join(" ")
+            )
+        }
+    }
+}
+
+impl RowMatrix {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Parses row of notes (eg. "C B G A♭") or pitch classes, C being 0
+/// and 10 and 11 written as t and e if wished (eg. "0 e 7 8").
+/// Notes are separated by whitespace or commas.
+pub fn parse_row(input: &str) -> Result<Vec<(char, i8)>, String> {
+    let numeric = input.contains(char::is_numeric);
+
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|token| {
+            let pc = match &token.to_lowercase()[..] {
+                "t" => Some(10),
+                "e" if numeric => Some(11),
+                t => t.parse::<u8>().ok(),
+            };
+
+            match pc {
+                Some(pc) if pc < 12 => Ok(util::pitch_class_to_note(pc)),
+                Some(_) => Err(format!("Invalid pitch class: {}", token)),
+                None => {
+                    let note = util::str_to_note(token);
+                    if !('a'..='g').contains(&note.0) || util::note_to_str(note).len() < token.len()
+                    {
+                        return Err(format!("Unknown note: {}", token));
+                    }
+                    Ok(note)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Checks that row holds each of the twelve pitch classes once
+pub fn validate(row: &[(char, i8)]) -> Result<(), String> {
+    let pcs: Vec<u8> = row.iter().map(|&n| util::pitch_class(n)).collect();
+
+    if let Some((i, _)) = pcs
+        .iter()
+        .enumerate()
+        .find(|(i, pc)| pcs[..*i].contains(pc))
+    {
+        return Err(format!(
+            "Repeated pitch class: {}",
+            util::note_to_str(row[i]).to_uppercase()
+        ));
+    }
+
+    if pcs.len() != 12 {
+        let missing: Vec<String> = (0..12)
+            .filter(|pc| !pcs.contains(pc))
+            .map(|pc| util::note_to_str(util::pitch_class_to_note(pc)).to_uppercase())
+            .collect();
+        return Err(format!("Missing pitch classes: {}", missing.join(" ")));
+    }
+
+    Ok(())
+}
+
+// Spelling of pitch class as found in row
+fn spell(row: &[(char, i8)], pc: u8) -> (char, i8) {
+    util::spell_pitch_class(pc % 12, row, false)
+}
+
+/// Returns form of row transposed by semitones from its first note
+/// (eg. I5 is the inversion starting 5 semitones above the row)
+pub fn form(row: &[(char, i8)], form: Form, transposition: u8) -> Vec<(char, i8)> {
+    let pcs: Vec<u8> = row.iter().map(|&n| util::pitch_class(n)).collect();
+    let first = match pcs.first() {
+        Some(&pc) => pc,
+        None => return vec![],
+    };
+
+    let mut result: Vec<(char, i8)> = pcs
+        .iter()
+        .map(|&pc| {
+            let interval = match form {
+                Form::Prime | Form::Retrograde => (pc + 12 - first) % 12,
+                Form::Inversion | Form::RetrogradeInversion => (first + 12 - pc) % 12,
+            };
+            spell(row, first + transposition % 12 + interval)
+        })
+        .collect();
+
+    if form == Form::Retrograde || form == Form::RetrogradeInversion {
+        result.reverse();
+    }
+    result
+}
+
+fn hexachord(notes: &[(char, i8)], second: bool) -> Vec<u8> {
+    let mut pcs: Vec<u8> = notes[if second { 6 } else { 0 }..][..6]
+        .iter()
+        .map(|&n| util::pitch_class(n))
+        .collect();
+    pcs.sort_unstable();
+    pcs
+}
+
+/// Returns forms whose first hexachord holds the notes of the second
+/// hexachord of the row, so that both sound the twelve pitch classes
+/// together. R0 always does and is left out.
+pub fn combinatoriality(row: &[(char, i8)]) -> Vec<String> {
+    if validate(row).is_err() {
+        return vec![];
+    }
+
+    let complement = hexachord(row, true);
+    let mut result = vec![];
+
+    for f in [
+        Form::Prime,
+        Form::Inversion,
+        Form::Retrograde,
+        Form::RetrogradeInversion,
+    ] {
+        for t in 0..12 {
+            if f == Form::Retrograde && t == 0 {
+                continue;
+            }
+            if hexachord(&form(row, f, t), false) == complement {
+                result.push(format!("{}{}", String::from(f), t));
+            }
+        }
+    }
+
+    result
+}
+
+/// Builds twelve-tone matrix of row, with primes as rows
+/// and inversions as columns
+pub fn matrix(row: &[(char, i8)]) -> Result<RowMatrix, String> {
+    validate(row)?;
+
+    let inversion = form(row, Form::Inversion, 0);
+    let first = util::pitch_class(row[0]);
+    let transposition = |n: (char, i8)| (util::pitch_class(n) + 12 - first) % 12;
+
+    Ok(RowMatrix {
+        row: row.iter().map(|&n| util::note_to_str(n)).collect(),
+        matrix: inversion
+            .iter()
+            .map(|&n| {
+                form(row, Form::Prime, transposition(n))
+                    .into_iter()
+                    .map(util::note_to_str)
+                    .collect()
+            })
+            .collect(),
+        primes: inversion
+            .iter()
+            .map(|&n| format!("P{}", transposition(n)))
+            .collect(),
+        inversions: row
+            .iter()
+            .map(|&n| format!("I{}", transposition(n)))
+            .collect(),
+        combinatorial: combinatoriality(row),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chromatic_row_combinatoriality() {
+        let row = parse_row("0 1 2 3 4 5 6 7 8 9 t e").unwrap();
+        assert_eq!(combinatoriality(&row), vec!["P6", "I11", "RI5"]);
+    }
+
+    #[test]
+    fn matrix_rows_and_labels() {
+        let row = parse_row("C B G A♭ E♭ D♭ D B♭ F# F E A").unwrap();
+        let m = matrix(&row).unwrap();
+        assert_eq!(m.primes[..3], ["P0", "P1", "P5"]);
+        assert_eq!(m.inversions[..3], ["I0", "I11", "I7"]);
+        assert_eq!(m.matrix[1][..3], ["d♭", "c", "a♭"]);
+        assert_eq!(
+            m.matrix.iter().map(|r| &r[0][..]).collect::<Vec<&str>>()[..4],
+            ["c", "d♭", "f", "e"]
+        );
+    }
+
+    #[test]
+    fn forms_of_row() {
+        let row = parse_row("0 e 7 8 3 1 2 t 6 5 4 9").unwrap();
+        let pcs = |notes: Vec<(char, i8)>| -> Vec<u8> {
+            notes.into_iter().map(util::pitch_class).collect()
+        };
+        assert_eq!(
+            pcs(form(&row, Form::Inversion, 0)),
+            vec![0, 1, 5, 4, 9, 11, 10, 2, 6, 7, 8, 3]
+        );
+        assert_eq!(
+            pcs(form(&row, Form::Retrograde, 0)),
+            vec![9, 4, 5, 6, 10, 2, 1, 3, 8, 7, 11, 0]
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_rows() {
+        assert_eq!(
+            validate(&parse_row("0 1 2 3 4 5 6 7 8 9 t t").unwrap()),
+            Err("Repeated pitch class: A#".to_string())
+        );
+        assert_eq!(
+            validate(&parse_row("0 1 2 3 4 5 6 7 8 9 t").unwrap()),
+            Err("Missing pitch classes: B".to_string())
+        );
+        assert!(parse_row("0 1 12").is_err());
+    }
+}
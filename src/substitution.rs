@@ -0,0 +1,298 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+const MAJOR: [u8; 3] = [0, 4, 7];
+const MINOR: [u8; 3] = [0, 3, 7];
+const DOMINANT7: [u8; 4] = [0, 4, 7, 10];
+const MINOR7: [u8; 4] = [0, 3, 7, 10];
+const MAJOR7: [u8; 4] = [0, 4, 7, 11];
+const HALFDIM7: [u8; 4] = [0, 3, 6, 10];
+const DIM7: [u8; 4] = [0, 3, 6, 9];
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Substitution {
+    pub kind: String,
+    /// Substitute chords, in playing order
+    pub chords: Vec<Chord>,
+    pub rationale: String,
+    /// Notes shared with the original chord
+    pub common_tones: Vec<String>,
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<18} {1:<18} common: {2:<12} {3:}",
+            &self.kind,
+            &self
+                .chords
+                .iter()
+                .map(|c| c.name.clone())
+                .collect::<Vec<String>>()
+                .join(" "),
+            &self
+                .common_tones
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(", "),
+            &self.rationale
+        )
+    }
+}
+
+// Key context for building substitutes
+struct Key {
+    tonic: u8,
+    notes: Vec<(char, i8)>,
+    flats: bool,
+}
+
+impl Key {
+    fn new(notes: &[String]) -> Key {
+        let notes: Vec<(char, i8)> = notes.iter().map(|n| util::str_to_note(n)).collect();
+
+        Key {
+            tonic: util::pitch_class(notes[0]),
+            flats: !notes.iter().any(|n| n.1 > 0),
+            notes,
+        }
+    }
+
+    fn chord(&self, root: u8, intervals: &[u8]) -> Chord {
+        self.spelled_chord(root, intervals, self.flats)
+    }
+
+    fn spelled_chord(&self, root: u8, intervals: &[u8], flats: bool) -> Chord {
+        let root = util::spell_pitch_class(root % 12, &self.notes, flats);
+        Chord::from_intervals(root, intervals.to_vec(), &self.notes)
+    }
+}
+
+fn common_tones(chord: &Chord, chords: &[Chord]) -> Vec<String> {
+    let pcs: Vec<u8> = chords.iter().flat_map(|c| c.pitch_classes()).collect();
+
+    chord
+        .notes
+        .iter()
+        .filter(|n| pcs.contains(&util::pitch_class(util::str_to_note(n))))
+        .cloned()
+        .collect()
+}
+
+// Plain stacked thirds, without adds or omissions
+fn tertian(chord: &Chord) -> bool {
+    let intervals = chord.intervals();
+    let has = |i: u8| intervals.contains(&i);
+
+    (has(3) || has(4))
+        && (has(6) || has(7) || has(8))
+        && !(has(7) && has(8))
+        && !has(1)
+        && !has(2)
+        && !has(5)
+}
+
+fn same_tones(a: &Chord, b: &Chord) -> bool {
+    let mut a = a.pitch_classes();
+    let mut b = b.pitch_classes();
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
+
+fn substitution(chord: &Chord, kind: &str, chords: Vec<Chord>, rationale: String) -> Substitution {
+    let substitutes: Vec<Chord> = chords.iter().filter(|&c| c != chord).cloned().collect();
+
+    Substitution {
+        kind: kind.to_string(),
+        common_tones: common_tones(chord, &substitutes),
+        chords,
+        rationale,
+    }
+}
+
+/// Returns standard substitutions for chord in given key and scale
+pub fn substitutions(chord: &Chord, key: &str, scale: &str) -> Vec<Substitution> {
+    let (notes, diatonic) = crate::analyze(key, scale, false);
+    let key = Key::new(&notes);
+
+    let root = util::pitch_class(chord.root());
+    let intervals = chord.intervals();
+    let has = |i: u8| intervals.contains(&i);
+
+    let dominant = has(4) && has(10);
+    let major = has(4) && has(7) && !has(10);
+    let minor = has(3) && has(7) && !has(6);
+    let seventh = has(10) || has(11);
+    let name = &chord.name;
+
+    let mut result = vec![];
+
+    // tritone substitute
+    if dominant {
+        let sub = key.chord(root + 6, &DOMINANT7);
+        let rationale = format!(
+            "shares the tritone {} and resolves down by half step",
+            common_tones(chord, std::slice::from_ref(&sub))
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join("-")
+        );
+        result.push(substitution(chord, "tritone", vec![sub], rationale));
+
+        // backdoor dominant resolves to same target from a whole step below
+        let target = key.chord(root + 5, &MAJOR);
+        let sub = key.chord(root + 3, &DOMINANT7);
+        let rationale = format!("bVII7 of {} as backdoor resolution", target.name);
+        result.push(substitution(chord, "backdoor", vec![sub], rationale));
+    }
+
+    // relative major and minor
+    if major {
+        let sub = key.chord(root + 9, if seventh { &MINOR7 } else { &MINOR });
+        let rationale = format!("relative minor of {}", name);
+        result.push(substitution(chord, "relative minor", vec![sub], rationale));
+    } else if minor {
+        let sub = key.chord(root + 3, if seventh { &MAJOR7 } else { &MAJOR });
+        let rationale = format!("relative major of {}", name);
+        result.push(substitution(chord, "relative major", vec![sub], rationale));
+    }
+
+    // tonic can be approached through its backdoor dominant
+    if root == key.tonic && major {
+        let sub = key.chord(root + 10, &DOMINANT7);
+        let rationale = format!("bVII7 borrowed from minor, resolving to {}", name);
+        result.push(substitution(
+            chord,
+            "backdoor",
+            vec![sub, chord.clone()],
+            rationale,
+        ));
+    }
+
+    // diminished passing chords, leading tone spelled sharp
+    let sub = key.spelled_chord(root + 11, &DIM7, false);
+    let rationale = format!("leading tone diminished 7th into {}", name);
+    result.push(substitution(
+        chord,
+        "passing dim",
+        vec![sub, chord.clone()],
+        rationale,
+    ));
+
+    let sub = key.chord(root + 1, &DIM7);
+    let rationale = format!("chromatic diminished 7th above {}", name);
+    result.push(substitution(
+        chord,
+        "passing dim",
+        vec![chord.clone(), sub],
+        rationale,
+    ));
+
+    // secondary dominant and related ii-V
+    if major || minor || dominant {
+        let five = key.chord(root + 7, &DOMINANT7);
+        let two = key.chord(root + 2, if minor { &HALFDIM7 } else { &MINOR7 });
+
+        if root != key.tonic {
+            let rationale = format!("secondary dominant V7/{} tonicizing it", name);
+            result.push(substitution(
+                chord,
+                "secondary dominant",
+                vec![five.clone(), chord.clone()],
+                rationale,
+            ));
+        }
+
+        let rationale = format!("related ii-V leading into {}", name);
+        result.push(substitution(
+            chord,
+            "ii-V",
+            vec![two, five, chord.clone()],
+            rationale,
+        ));
+    }
+
+    // modal interchange from parallel key
+    let parallel = match scale {
+        "major" | "ionian" | "lydian" | "mixolydian" => "minor",
+        _ => "major",
+    };
+    let tonic = util::note_to_str(key.notes[0]);
+    let (_, borrowed) = crate::analyze(&tonic, parallel, false);
+    for c in borrowed.iter().filter(|c| {
+        util::pitch_class(c.root()) == root
+            && c.notes.len() == chord.notes.len()
+            && !same_tones(c, chord)
+            && !diatonic.iter().any(|d| same_tones(d, c))
+            && tertian(c)
+    }) {
+        let rationale = format!("borrowed from {} {}", tonic.to_uppercase(), parallel);
+        result.push(substitution(
+            chord,
+            "modal interchange",
+            vec![c.clone()],
+            rationale,
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(subs: &'a [Substitution], kind: &str) -> Vec<&'a Substitution> {
+        subs.iter().filter(|s| s.kind == kind).collect()
+    }
+
+    fn names(sub: &Substitution) -> Vec<String> {
+        sub.chords.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[test]
+    fn tritone_substitute_of_dominant() {
+        let g7 = Chord::from_intervals(('g', 0), vec![0, 4, 7, 10], &[]);
+        let subs = substitutions(&g7, "c", "major");
+        let tritone = find(&subs, "tritone");
+
+        assert_eq!(tritone.len(), 1);
+        assert_eq!(names(tritone[0]), vec!["D♭(7)"]);
+        assert_eq!(tritone[0].chords[0].notes, vec!["d♭", "f", "a♭", "b"]);
+        assert_eq!(tritone[0].common_tones, vec!["b", "f"]);
+    }
+
+    #[test]
+    fn relative_substitutes() {
+        let c = Chord::from_intervals(('c', 0), vec![0, 4, 7], &[]);
+        let subs = substitutions(&c, "c", "major");
+        assert_eq!(names(find(&subs, "relative minor")[0]), vec!["Am"]);
+
+        let dm7 = Chord::from_intervals(('d', 0), vec![0, 3, 7, 10], &[]);
+        let subs = substitutions(&dm7, "c", "major");
+        let relative = find(&subs, "relative major");
+        assert_eq!(names(relative[0]), vec!["F(M7)"]);
+        assert_eq!(relative[0].common_tones, vec!["f", "a", "c"]);
+    }
+
+    #[test]
+    fn diminished_passing_chords() {
+        let c = Chord::from_intervals(('c', 0), vec![0, 4, 7], &[]);
+        let subs = substitutions(&c, "c", "major");
+        let dim = find(&subs, "passing dim");
+
+        assert_eq!(dim.len(), 2);
+        // leading tone diminished 7th below, chromatic one above
+        assert_eq!(dim[0].chords[0].notes, vec!["b", "d", "f", "g#"]);
+        assert_eq!(names(dim[0])[1], "C");
+        assert_eq!(names(dim[1])[0], "C");
+        assert_eq!(dim[1].chords[1].notes, vec!["d♭", "e", "g", "b♭"]);
+    }
+}
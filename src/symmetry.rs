@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::scale;
+use serde_derive::{Deserialize, Serialize};
+
+// Messiaen's modes of limited transposition, in order
+const MESSIAEN: [&str; 7] = [
+    "messiaen1",
+    "messiaen2",
+    "messiaen3",
+    "messiaen4",
+    "messiaen5",
+    "messiaen6",
+    "messiaen7",
+];
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Symmetry {
+    pub scale: String,
+    /// Semitones above root of scale notes
+    pub pitch_classes: Vec<u8>,
+    /// Number of distinct transpositions, 12 for asymmetric scales
+    pub transpositions: u8,
+    /// Order of rotational symmetry, 1 for asymmetric scales
+    pub symmetry: u8,
+    /// Transpositions mapping scale onto itself, in semitones
+    pub periods: Vec<u8>,
+    /// Number of Messiaen's mode matching scale in some transposition
+    pub messiaen: Option<u8>,
+}
+
+impl Symmetry {
+    /// Scale of limited transposition, having less than 12 transpositions
+    pub fn is_limited(&self) -> bool {
+        self.transpositions < 12
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<16} transpositions: {1:<3} symmetry: {2:<3} periods: {3:<14} {4:}",
+            scale::friendly_name(&self.scale),
+            self.transpositions,
+            self.symmetry,
+            self.periods
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+            match self.messiaen {
+                Some(m) => format!("Messiaen mode {}", m),
+                None if self.is_limited() => String::from("limited transposition"),
+                None => String::new(),
+            }
+        )
+    }
+}
+
+fn pitch_classes(scale: &str) -> Vec<u8> {
+    let mut pcs: Vec<u8> = scale::get_scale(scale).iter().map(|i| i % 12).collect();
+    pcs.sort_unstable();
+    pcs.dedup();
+    pcs
+}
+
+fn transpose(pcs: &[u8], semitones: u8) -> Vec<u8> {
+    let mut result: Vec<u8> = pcs.iter().map(|pc| (pc + semitones) % 12).collect();
+    result.sort_unstable();
+    result
+}
+
+/// Analyzes transpositional symmetry of scale by name or custom steps.
+/// Scales mapping onto themselves when transposed are modes of limited
+/// transposition, Messiaen's seven modes being matched in any transposition.
+pub fn analyze_symmetry(scale: &str) -> Symmetry {
+    let pcs = pitch_classes(scale);
+    let periods: Vec<u8> = (0..12).filter(|&t| transpose(&pcs, t) == pcs).collect();
+    let symmetry = periods.len() as u8;
+
+    let messiaen = MESSIAEN
+        .iter()
+        .position(|m| {
+            let mode = pitch_classes(m);
+            (0..12).any(|t| transpose(&mode, t) == pcs)
+        })
+        .map(|i| i as u8 + 1);
+
+    Symmetry {
+        scale: scale.to_string(),
+        pitch_classes: pcs,
+        transpositions: 12 / symmetry,
+        symmetry,
+        periods,
+        messiaen,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transpositions(scale: &str) -> u8 {
+        analyze_symmetry(scale).transpositions
+    }
+
+    #[test]
+    fn transpositions_of_messiaen_modes() {
+        assert_eq!(transpositions("wholetone"), 2);
+        assert_eq!(transpositions("octatonic"), 3);
+        let counts: Vec<u8> = MESSIAEN.iter().map(|m| transpositions(m)).collect();
+        assert_eq!(counts, vec![2, 3, 4, 6, 6, 6, 6]);
+    }
+
+    #[test]
+    fn asymmetric_scales_have_twelve_transpositions() {
+        for scale in ["major", "harmonicminor", "pentatonic"] {
+            let s = analyze_symmetry(scale);
+            assert_eq!(s.transpositions, 12);
+            assert_eq!(s.periods, vec![0]);
+            assert!(!s.is_limited());
+            assert_eq!(s.messiaen, None);
+        }
+    }
+
+    #[test]
+    fn matches_messiaen_mode_in_any_transposition() {
+        assert_eq!(analyze_symmetry("wholetone").messiaen, Some(1));
+        // diminished scale starting with a whole step
+        assert_eq!(analyze_symmetry("2,1,2,1,2,1,2").messiaen, Some(2));
+        assert_eq!(analyze_symmetry("augmented").messiaen, None);
+        assert!(analyze_symmetry("augmented").is_limited());
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Metric {
+    Weight,
+    Dissonance,
+    Roughness,
+    Harmonicity,
+}
+
+impl From<Metric> for String {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::Weight => "weight",
+            Metric::Dissonance => "dissonance",
+            Metric::Roughness => "roughness",
+            Metric::Harmonicity => "harmonicity",
+        }
+        .into()
+    }
+}
+
+impl From<String> for Metric {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "dissonance" => Metric::Dissonance,
+            "roughness" => Metric::Roughness,
+            "harmonicity" => Metric::Harmonicity,
+            _ => Metric::Weight,
+        }
+    }
+}
+
+/// Harmonic spectrum of a tone, used for roughness.
+/// Partial k has amplitude rolloff^(k-1).
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct Spectrum {
+    pub partials: u8,
+    pub rolloff: f32,
+}
+
+impl Default for Spectrum {
+    fn default() -> Self {
+        Spectrum {
+            partials: 6,
+            rolloff: 0.88,
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone, Copy)]
+pub struct Tension {
+    /// Mean interval class dissonance of all note pairs
+    pub dissonance: f32,
+    /// Sensory roughness of close position voicing
+    pub roughness: f32,
+    /// Inverse of relative periodicity in just intonation, 1 for a single tone
+    pub harmonicity: f32,
+}
+
+impl fmt::Display for Tension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "dissonance {:>6.3}  roughness {:>6.3}  harmonicity {:>5.3}",
+            self.dissonance, self.roughness, self.harmonicity
+        )
+    }
+}
+
+impl Tension {
+    /// Computes tension of chord intervals (semitones above root)
+    /// with root at given pitch class, voiced in close position from 4th octave
+    pub fn new(root: u8, intervals: &[u8], spectrum: &Spectrum) -> Tension {
+        let mut intervals = intervals.to_vec();
+        intervals.sort_unstable();
+        intervals.dedup();
+
+        Tension {
+            dissonance: dissonance(&intervals),
+            roughness: roughness(root, &intervals, spectrum),
+            harmonicity: harmonicity(&intervals),
+        }
+    }
+
+    /// Returns metric as sort key, lower being less tense
+    pub fn key(&self, metric: Metric) -> f32 {
+        match metric {
+            Metric::Dissonance => self.dissonance,
+            Metric::Roughness => self.roughness,
+            Metric::Harmonicity => -self.harmonicity,
+            Metric::Weight => 0.0,
+        }
+    }
+}
+
+// Negated aggregate dyadic consonance of interval classes 1-6 (Huron 1994)
+const INTERVAL_CLASS: [f32; 6] = [1.428, 0.582, -0.594, -0.386, -1.240, 0.453];
+
+fn dissonance(intervals: &[u8]) -> f32 {
+    let mut sum = 0.0;
+    let mut pairs = 0;
+
+    for (i, a) in intervals.iter().enumerate() {
+        for b in intervals[i + 1..].iter() {
+            let ic = std::cmp::min(b - a, 12 - (b - a));
+            if ic > 0 {
+                sum += INTERVAL_CLASS[ic as usize - 1];
+                pairs += 1;
+            }
+        }
+    }
+
+    if pairs > 0 {
+        sum / pairs as f32
+    } else {
+        0.0
+    }
+}
+
+// Plomp-Levelt dissonance curve of two partials (Sethares 1993)
+fn partial_roughness(f1: f32, f2: f32, a1: f32, a2: f32) -> f32 {
+    let (low, high) = if f1 < f2 { (f1, f2) } else { (f2, f1) };
+    let s = 0.24 / (0.0207 * low + 18.96);
+    let d = high - low;
+
+    a1.min(a2) * ((-3.5 * s * d).exp() - (-5.75 * s * d).exp())
+}
+
+fn roughness(root: u8, intervals: &[u8], spectrum: &Spectrum) -> f32 {
+    let partials: Vec<Vec<(f32, f32)>> = intervals
+        .iter()
+        .map(|&i| {
+            let midi = 60.0 + (root % 12) as f32 + i as f32;
+            let f0 = 440.0 * 2f32.powf((midi - 69.0) / 12.0);
+            (1..=spectrum.partials)
+                .map(|k| (f0 * k as f32, spectrum.rolloff.powi(k as i32 - 1)))
+                .collect()
+        })
+        .collect();
+
+    // roughness between partials of different notes
+    let mut sum = 0.0;
+    for (i, a) in partials.iter().enumerate() {
+        for b in partials[i + 1..].iter() {
+            for &(f1, a1) in a {
+                for &(f2, a2) in b {
+                    sum += partial_roughness(f1, f2, a1, a2);
+                }
+            }
+        }
+    }
+
+    sum
+}
+
+// Just intonation ratios of semitones above root
+const RATIOS: [(u64, u64); 12] = [
+    (1, 1),
+    (16, 15),
+    (9, 8),
+    (6, 5),
+    (5, 4),
+    (4, 3),
+    (45, 32),
+    (3, 2),
+    (8, 5),
+    (5, 3),
+    (9, 5),
+    (15, 8),
+];
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+// Relative periodicity of chord: lcm of ratio denominators
+// relative to the lowest note (Stolzenburg 2015)
+fn harmonicity(intervals: &[u8]) -> f32 {
+    let lowest = match intervals.first() {
+        Some(&i) => RATIOS[i as usize % 12],
+        None => return 1.0,
+    };
+
+    let period = intervals.iter().fold(1, |acc, &i| {
+        let ratio = RATIOS[i as usize % 12];
+        // ratio / lowest
+        let (p, q) = (ratio.0 * lowest.1, ratio.1 * lowest.0);
+        let g = gcd(p, q);
+        lcm(acc, q / g)
+    });
+
+    1.0 / (1.0 + (period as f32).log2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tension(intervals: &[u8]) -> Tension {
+        Tension::new(0, intervals, &Spectrum::default())
+    }
+
+    #[test]
+    fn roughness_falls_with_wider_intervals() {
+        let minor_second = tension(&[0, 1]).roughness;
+        let major_third = tension(&[0, 4]).roughness;
+        let fifth = tension(&[0, 7]).roughness;
+
+        assert!(minor_second > major_third);
+        assert!(major_third > fifth);
+    }
+
+    #[test]
+    fn major_triad_is_more_harmonic_than_augmented() {
+        assert!(tension(&[0, 4, 7]).harmonicity > tension(&[0, 4, 8]).harmonicity);
+        assert_eq!(tension(&[0]).harmonicity, 1.0);
+    }
+
+    #[test]
+    fn dissonance_of_interval_classes() {
+        assert!(tension(&[0, 1]).dissonance > tension(&[0, 6]).dissonance);
+        assert!(tension(&[0, 6]).dissonance > tension(&[0, 7]).dissonance);
+        // metric keys sort less tense chords first
+        assert!(
+            tension(&[0, 4, 7]).key(Metric::Harmonicity)
+                < tension(&[0, 4, 8]).key(Metric::Harmonicity)
+        );
+    }
+}
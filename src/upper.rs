@@ -0,0 +1,209 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::naming::{self, Style};
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+const TRIADS: [[u8; 3]; 4] = [[0, 4, 7], [0, 3, 7], [0, 3, 6], [0, 4, 8]];
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct UpperStructure {
+    /// Polychord name, upper over lower (eg. "D/C7" or "E♭|C")
+    pub name: String,
+    pub upper: Chord,
+    pub lower: Chord,
+    /// Degrees of upper triad notes above lower root
+    pub tensions: Vec<String>,
+}
+
+impl fmt::Display for UpperStructure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<12} ({1:}) over ({2:})  [{3:}]",
+            &self.name,
+            &self
+                .upper
+                .notes
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(", "),
+            &self
+                .lower
+                .notes
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<String>>()
+                .join(", "),
+            &self.tensions.join(", ")
+        )
+    }
+}
+
+fn symbol(chord: &Chord) -> String {
+    naming::name(chord.root(), &chord.intervals(), Style::Pop, &[])
+}
+
+fn upper_structure(upper: Chord, lower: Chord, slash: bool) -> UpperStructure {
+    let root = util::pitch_class(lower.root());
+
+    // lower seventh chords are named with their fifth, even when left out
+    let lower_name = if slash {
+        let mut intervals = lower.intervals();
+        if !intervals.iter().any(|&i| (6..=8).contains(&i)) {
+            intervals.push(7);
+        }
+        naming::name(lower.root(), &intervals, Style::Pop, &[])
+    } else {
+        symbol(&lower)
+    };
+
+    UpperStructure {
+        name: format!(
+            "{}{}{}",
+            symbol(&upper),
+            if slash { "/" } else { "|" },
+            lower_name
+        ),
+        tensions: upper
+            .pitch_classes()
+            .iter()
+            .map(|&pc| util::tension_label(pc + 12 - root))
+            .collect(),
+        upper,
+        lower,
+    }
+}
+
+/// Breaks chord into triads sitting on top of its lower part.
+/// Lower part is either the shell of root, 3rd and 7th,
+/// named as slash polychord (eg. "D/C7"), or the root triad,
+/// named as stacked polychord (eg. "E♭|C").
+/// Upper triad has to cover all chord tones left from the lower part,
+/// but for the perfect 5th, which the shell leaves out.
+pub fn decompose(chord: &Chord) -> Vec<UpperStructure> {
+    let mut result = vec![];
+
+    let root = chord.root();
+    let intervals = chord.intervals();
+    let has = |i: u8| intervals.contains(&i);
+    let spelling: Vec<(char, i8)> = chord.notes.iter().map(|n| util::str_to_note(n)).collect();
+
+    let third = [4, 3].iter().copied().find(|&i| has(i));
+    let fifth = [7, 6, 8].iter().copied().find(|&i| has(i));
+    let seventh = [10, 11].iter().copied().find(|&i| has(i));
+
+    let third = match third {
+        Some(t) => t,
+        None => return result,
+    };
+
+    let mut lowers = vec![];
+    if let Some(seventh) = seventh {
+        lowers.push((vec![0, third, seventh], true));
+    }
+    if let Some(fifth) = fifth {
+        lowers.push((vec![0, third, fifth], false));
+    }
+
+    for (lower, slash) in lowers {
+        for &upper_root in intervals.iter() {
+            for triad in TRIADS.iter() {
+                let upper: Vec<u8> = triad.iter().map(|t| (upper_root + t) % 12).collect();
+
+                let covered = upper.iter().all(|u| has(*u))
+                    && intervals
+                        .iter()
+                        .all(|i| *i == 7 || lower.contains(i) || upper.contains(i));
+                // upper triad has to add a 9th, 11th or 13th, plain or altered
+                let tension = upper
+                    .iter()
+                    .any(|u| !lower.contains(u) && [1, 2, 3, 5, 6, 8, 9].contains(u));
+
+                if covered && tension {
+                    let root_pc = util::pitch_class(root);
+                    let upper_note =
+                        util::spell_pitch_class(root_pc + upper_root, &spelling, false);
+
+                    result.push(upper_structure(
+                        Chord::from_intervals(upper_note, triad.to_vec(), &spelling),
+                        Chord::from_intervals(root, lower.clone(), &spelling),
+                        slash,
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns common upper structure triads of dominant 7th chord on root
+pub fn dominant_options(root: (char, i8)) -> Vec<UpperStructure> {
+    let root_pc = util::pitch_class(root);
+    let flats = root.1 < 0 || root == ('f', 0);
+    let seventh = util::spell_pitch_class(root_pc + 10, &[], true);
+    let lower = Chord::from_intervals(root, vec![0, 4, 10], &[seventh]);
+
+    // II, ♭III, ♭V, ♭VI and VI major triads
+    [2, 3, 6, 8, 9]
+        .iter()
+        .map(|&degree| {
+            let note =
+                util::spell_pitch_class(root_pc + degree, &[], flats || ![2, 9].contains(&degree));
+            upper_structure(
+                Chord::from_intervals(note, vec![0, 4, 7], &[]),
+                lower.clone(),
+                true,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(intervals: Vec<u8>) -> Vec<String> {
+        decompose(&Chord::from_intervals(('c', 0), intervals, &[]))
+            .into_iter()
+            .map(|u| u.name)
+            .collect()
+    }
+
+    #[test]
+    fn dominant_with_upper_triad_on_second() {
+        let c7 = Chord::from_intervals(('c', 0), vec![0, 4, 10, 2, 6, 9], &[]);
+        let upper = decompose(&c7);
+
+        assert_eq!(upper.len(), 1);
+        assert_eq!(upper[0].name, "D/C7");
+        assert_eq!(upper[0].tensions, vec!["9", "#11", "13"]);
+    }
+
+    #[test]
+    fn full_thirteenth_keeps_fifth_out_of_cover() {
+        assert_eq!(names(vec![0, 4, 7, 10, 2, 6, 9]), vec!["D/C7"]);
+    }
+
+    #[test]
+    fn dominant_options_of_g7() {
+        let names: Vec<String> = dominant_options(('g', 0))
+            .into_iter()
+            .map(|u| u.name)
+            .collect();
+        assert_eq!(names, vec!["A/G7", "B♭/G7", "D♭/G7", "E♭/G7", "E/G7"]);
+    }
+
+    #[test]
+    fn chord_tones_alone_are_not_upper_structures() {
+        assert!(names(vec![0, 4, 7, 11]).is_empty());
+    }
+
+    #[test]
+    fn upper_triad_adds_tension() {
+        assert_eq!(names(vec![0, 2, 4, 7, 11]), vec!["G/Cmaj7", "G|C"]);
+    }
+}
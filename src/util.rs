@@ -1,3 +1,5 @@
+use crate::scale;
+use crate::scoring::{ChordScorer, DefaultScorer};
 use crate::Chord;
 
 /// Formats notes for easier readability
@@ -7,7 +9,7 @@ pub fn formatted_notes(notes: Vec<(char, i8)>) -> Vec<(char, i8)> {
 
     for note in notes {
         //If note key exists in formatted, attempt to add alt note
-        if formatted.iter().position(|&v| v.0 == note.0) != None {
+        if formatted.iter().position(|&v| v.0 == note.0).is_some() {
             formatted.push(alt_note(note));
         } else {
             formatted.push(note);
@@ -32,6 +34,7 @@ pub fn alt_note(note: (char, i8)) -> (char, i8) {
         "e♭" => "d#",
         "e#" => "f",
         "f" => "e#",
+        "f♭" => "e",
         "f#" => "g♭",
         "g♭" => "f#",
         "g#" => "a♭",
@@ -63,7 +66,7 @@ pub fn note_to_str(note: (char, i8)) -> String {
 pub fn str_to_note(note: &str) -> (char, i8) {
     let n = note.to_lowercase();
 
-    let mut a = (n.chars().nth(0).unwrap(), 0);
+    let mut a = (n.chars().next().unwrap(), 0);
 
     if n.len() > 1 {
         a.1 = match n.chars().nth(1).unwrap() {
@@ -123,6 +126,11 @@ pub fn weight_levels(a: &[u8]) -> i8 {
 
 //Deduplicate chords, prioritizing lower weight
 pub fn deduplicate(chords: Vec<Chord>) -> Vec<Chord> {
+    deduplicate_with(chords, &DefaultScorer)
+}
+
+//Deduplicate chords, prioritizing lower weight for chords scorer considers extended
+pub fn deduplicate_with(chords: Vec<Chord>, scorer: &dyn ChordScorer) -> Vec<Chord> {
     let mut retained: Vec<usize> = vec![];
 
     for (i, c) in chords[..].iter().enumerate() {
@@ -132,9 +140,9 @@ pub fn deduplicate(chords: Vec<Chord>) -> Vec<Chord> {
 
         let mut min = i;
 
-        if c.weight > 4 {
+        if scorer.is_extended(c.weight) {
             for (j, d) in chords[i + 1..].iter().enumerate() {
-                if c.name != d.name && c.equals(&d) && c.weight > d.weight {
+                if c.name != d.name && c.equals(d) && c.weight > d.weight {
                     min = i + 1 + j;
                 }
             }
@@ -150,3 +158,92 @@ pub fn deduplicate(chords: Vec<Chord>) -> Vec<Chord> {
         .map(|(_, c)| c.clone())
         .collect::<Vec<Chord>>()
 }
+
+/// Returns pitch class of note as semitones above C
+/// (eg. D -> 2, B♭ -> 10)
+pub fn pitch_class(note: (char, i8)) -> u8 {
+    scale::chromatic_notes(('c', 0))
+        .iter()
+        .position(|&v| v == note || v == alt_note(note))
+        .expect("Failed to find pitch class for note") as u8
+}
+
+/// Returns note for pitch class, spelled with sharps
+/// (eg. 10 -> A#)
+pub fn pitch_class_to_note(pc: u8) -> (char, i8) {
+    scale::chromatic_notes(('c', 0))[(pc % 12) as usize]
+}
+
+/// Returns midi note number for scientific pitch notation
+/// (eg. C4 -> 60, B♭3 -> 58)
+pub fn str_to_midi(pitch: &str) -> Option<u8> {
+    let split = pitch.find(|c: char| c.is_ascii_digit() || c == '-')?;
+    let octave = pitch[split..].parse::<i32>().ok()?;
+    if split == 0 {
+        return None;
+    }
+    let note = str_to_note(&pitch[..split]);
+    if !('a'..='g').contains(&note.0) || note_to_str(note).len() < split {
+        return None;
+    }
+    let natural = pitch_class((note.0, 0)) as i32;
+    let midi = (octave + 1) * 12 + natural + note.1 as i32;
+
+    if (0..128).contains(&midi) {
+        Some(midi as u8)
+    } else {
+        None
+    }
+}
+
+/// Returns scientific pitch notation of midi note, using given spelling
+/// (eg. 58, b♭ -> B♭3)
+pub fn midi_to_str(midi: u8, spelling: (char, i8)) -> String {
+    // octave follows the letter name, so that B#3 and C4 sound the same
+    let natural = midi as i32 - spelling.1 as i32;
+    format!(
+        "{}{}",
+        note_to_str(spelling).to_uppercase(),
+        natural.div_euclid(12) - 1
+    )
+}
+
+/// Spells note given semitones and letter names above root,
+/// falling back to plain pitch class spelling for double accidentals
+/// (eg. C, 3 semitones, 2 letters -> E♭)
+pub fn spell_interval(root: (char, i8), semitones: u8, letters: u8) -> (char, i8) {
+    let pc = (pitch_class(root) + semitones) % 12;
+    let letter = (b'a' + (root.0 as u8 - b'a' + letters) % 7) as char;
+    let alt = (pc as i8 - pitch_class((letter, 0)) as i8 + 18).rem_euclid(12) - 6;
+
+    if alt.abs() <= 1 {
+        (letter, alt)
+    } else {
+        spell_pitch_class(pc, &[], root.1 < 0)
+    }
+}
+
+/// Returns chord degree label of semitones above root
+/// (eg. 2 -> 9, 6 -> #11)
+pub fn tension_label(semitones: u8) -> String {
+    [
+        "1", "b9", "9", "#9", "3", "11", "#11", "5", "b13", "13", "b7", "7",
+    ][(semitones % 12) as usize]
+        .to_string()
+}
+
+/// Spells pitch class using given notes when possible,
+/// otherwise with sharps or flats
+pub fn spell_pitch_class(pc: u8, spelling: &[(char, i8)], flats: bool) -> (char, i8) {
+    let note = pitch_class_to_note(pc);
+
+    spelling
+        .iter()
+        .find(|&&n| pitch_class(n) == pc % 12)
+        .copied()
+        .unwrap_or(if flats && note.1 != 0 {
+            alt_note(note)
+        } else {
+            note
+        })
+}
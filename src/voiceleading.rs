@@ -0,0 +1,288 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::util;
+use crate::voicing::{self, VoicingOptions, VoicingType};
+use serde_derive::{Deserialize, Serialize};
+
+pub struct VoiceLeadingOptions {
+    /// Disallow voices crossing each other
+    pub no_crossing: bool,
+    /// Largest movement of a single voice, in semitones
+    pub max_leap: Option<u8>,
+    /// Range for voicing the first chord
+    pub range: VoicingOptions,
+}
+
+impl Default for VoiceLeadingOptions {
+    fn default() -> Self {
+        VoiceLeadingOptions {
+            no_crossing: true,
+            max_leap: None,
+            range: VoicingOptions::default(),
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct VoiceMotion {
+    pub from: String,
+    pub to: String,
+    /// Signed movement in semitones
+    pub semitones: i8,
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct VoiceLeading {
+    pub from: String,
+    pub to: String,
+    /// Target voicing as midi note numbers, lowest voice first
+    pub midi: Vec<u8>,
+    pub voices: Vec<VoiceMotion>,
+    /// Total semitone movement of all voices
+    pub distance: u8,
+}
+
+impl fmt::Display for VoiceLeading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}: {} ({})",
+            &self.from,
+            &self.to,
+            &self
+                .voices
+                .iter()
+                .map(|v| format!("{}>{}", v.from, v.to))
+                .collect::<Vec<String>>()
+                .join(" "),
+            self.distance
+        )
+    }
+}
+
+// Depth first search over voice targets, keeping the cheapest full cover
+fn search(
+    candidates: &[Vec<i32>],
+    from: &[i32],
+    pcs: &[u8],
+    no_crossing: bool,
+    current: &mut Vec<i32>,
+    best: &mut Option<(i32, Vec<i32>)>,
+) {
+    let cost: i32 = current.iter().zip(from).map(|(a, b)| (a - b).abs()).sum();
+
+    if best.as_ref().is_some_and(|b| cost >= b.0) {
+        return;
+    }
+
+    if current.len() == candidates.len() {
+        // every chord tone should sound when there are enough voices
+        let missing = pcs
+            .iter()
+            .filter(|&&pc| !current.iter().any(|&m| m.rem_euclid(12) as u8 == pc))
+            .count();
+        let missing = missing.saturating_sub(pcs.len().saturating_sub(current.len()));
+
+        // heavy penalty, so that a full cover is always preferred
+        let cost = cost + missing as i32 * 100;
+
+        if best.as_ref().is_none_or(|b| cost < b.0) {
+            *best = Some((cost, current.clone()));
+        }
+        return;
+    }
+
+    for &c in candidates[current.len()].iter() {
+        if no_crossing && current.last().is_some_and(|&l| c < l) {
+            continue;
+        }
+
+        current.push(c);
+        search(candidates, from, pcs, no_crossing, current, best);
+        current.pop();
+    }
+}
+
+/// Connects voicing to chord with minimal total voice movement.
+/// Common tones are held, as they cost nothing to keep.
+pub fn connect(
+    from: &[u8],
+    from_name: &str,
+    to: &Chord,
+    options: &VoiceLeadingOptions,
+) -> Option<VoiceLeading> {
+    let pcs = to.pitch_classes();
+    let from: Vec<i32> = from.iter().map(|&m| m as i32).collect();
+
+    // nearest pitch of each chord tone above and below every voice
+    let candidates: Vec<Vec<i32>> = from
+        .iter()
+        .map(|&m| {
+            let mut v: Vec<i32> = pcs
+                .iter()
+                .flat_map(|&pc| {
+                    let up = m + (pc as i32 - m).rem_euclid(12);
+                    vec![up, up - 12]
+                })
+                .filter(|&t| {
+                    (0..128).contains(&t)
+                        && options.max_leap.is_none_or(|l| (t - m).abs() <= l as i32)
+                })
+                .collect();
+            v.sort_by_key(|&t| (t - m).abs());
+            v.dedup();
+            v
+        })
+        .collect();
+
+    let mut best = None;
+    search(
+        &candidates,
+        &from,
+        &pcs,
+        options.no_crossing,
+        &mut vec![],
+        &mut best,
+    );
+
+    let (_, target) = best?;
+
+    let voices: Vec<VoiceMotion> = from
+        .iter()
+        .zip(target.iter())
+        .map(|(&a, &b)| VoiceMotion {
+            from: util::midi_to_str(a as u8, util::pitch_class_to_note(a as u8)),
+            to: util::midi_to_str(b as u8, to.spell(b as u8)),
+            semitones: (b - a) as i8,
+        })
+        .collect();
+
+    Some(VoiceLeading {
+        from: from_name.to_string(),
+        to: to.name.clone(),
+        midi: target.iter().map(|&m| m as u8).collect(),
+        distance: voices.iter().map(|v| v.semitones.unsigned_abs()).sum(),
+        voices,
+    })
+}
+
+/// Leads voices through a progression of chords,
+/// starting from close position voicing of the first chord
+pub fn lead(chords: &[Chord], options: &VoiceLeadingOptions) -> Vec<VoiceLeading> {
+    let mut result = vec![];
+
+    let first = match chords
+        .first()
+        .and_then(|c| voicing::voice(c, VoicingType::Close, &options.range))
+    {
+        Some(v) => v,
+        None => return result,
+    };
+
+    let mut current = first.midi.clone();
+    let mut spelled = first.notes.clone();
+
+    for pair in chords.windows(2) {
+        match connect(&current, &pair[0].name, &pair[1], options) {
+            Some(mut leading) => {
+                // keep spelling of previous chord on the from side
+                for (v, s) in leading.voices.iter_mut().zip(spelled.iter()) {
+                    v.from = s.clone();
+                }
+                current = leading.midi.clone();
+                spelled = leading.voices.iter().map(|v| v.to.clone()).collect();
+                result.push(leading);
+            }
+            None => break,
+        }
+    }
+
+    result
+}
+
+/// Renders voice leading as compact table, top voice first
+pub fn table(leadings: &[VoiceLeading]) -> String {
+    let first = match leadings.first() {
+        Some(l) => l,
+        None => return String::new(),
+    };
+
+    let mut columns: Vec<Vec<String>> = vec![];
+
+    let mut column = vec![first.from.clone()];
+    column.extend(first.voices.iter().rev().map(|v| v.from.clone()));
+    column.push(String::new());
+    columns.push(column);
+
+    for l in leadings {
+        let mut column = vec![l.to.clone()];
+        column.extend(l.voices.iter().rev().map(|v| {
+            if v.semitones == 0 {
+                format!("{} =", v.to)
+            } else {
+                format!("{} {:+}", v.to, v.semitones)
+            }
+        }));
+        column.push(l.distance.to_string());
+        columns.push(column);
+    }
+
+    let rows = columns[0].len();
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|c| c.iter().map(|s| s.chars().count()).max().unwrap_or(0) + 2)
+        .collect();
+
+    let mut lines = vec![];
+    for row in 0..rows {
+        let label = if row == 0 {
+            String::new()
+        } else if row == rows - 1 {
+            "total".to_string()
+        } else {
+            format!("v{}", rows - 1 - row)
+        };
+
+        let mut line = format!("{:<7}", label);
+        for (c, w) in columns.iter().zip(widths.iter()) {
+            line.push_str(&format!("{:<w$}", c[row], w = *w));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ii_v_i_moves_by_step() {
+        let chords = vec![
+            Chord::new("d", vec![0, 3, 7, 10], false, 0),
+            Chord::new("g", vec![0, 4, 7, 10], false, 0),
+            Chord::new("c", vec![0, 4, 7, 11], false, 0),
+        ];
+        let leadings = lead(&chords, &VoiceLeadingOptions::default());
+
+        assert_eq!(leadings.len(), 2);
+        for l in &leadings {
+            assert!(l.voices.iter().all(|v| v.semitones.abs() <= 2));
+            assert_eq!(l.distance, 3);
+        }
+        // voices never cross
+        assert!(leadings
+            .iter()
+            .all(|l| l.midi.windows(2).all(|w| w[0] < w[1])));
+    }
+
+    #[test]
+    fn common_tones_are_held() {
+        let am = Chord::new("a", vec![0, 3, 7], false, 0);
+        let leading = connect(&[48, 52, 55], "C", &am, &VoiceLeadingOptions::default()).unwrap();
+        assert_eq!(leading.midi, vec![48, 52, 57]);
+        assert_eq!(leading.distance, 2);
+    }
+}
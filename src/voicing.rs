@@ -0,0 +1,357 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::util;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum VoicingType {
+    Close,
+    Open,
+    Drop2,
+    Drop3,
+    Drop24,
+    Spread,
+    Shell,
+    RootlessA,
+    RootlessB,
+}
+
+impl From<VoicingType> for String {
+    fn from(kind: VoicingType) -> Self {
+        match kind {
+            VoicingType::Close => "close",
+            VoicingType::Open => "open",
+            VoicingType::Drop2 => "drop2",
+            VoicingType::Drop3 => "drop3",
+            VoicingType::Drop24 => "drop24",
+            VoicingType::Spread => "spread",
+            VoicingType::Shell => "shell",
+            VoicingType::RootlessA => "rootlessa",
+            VoicingType::RootlessB => "rootlessb",
+        }
+        .into()
+    }
+}
+
+impl From<String> for VoicingType {
+    fn from(s: String) -> Self {
+        match &s[..] {
+            "open" => VoicingType::Open,
+            "drop2" => VoicingType::Drop2,
+            "drop3" => VoicingType::Drop3,
+            "drop24" | "drop2&4" => VoicingType::Drop24,
+            "spread" => VoicingType::Spread,
+            "shell" => VoicingType::Shell,
+            "rootlessa" | "a" => VoicingType::RootlessA,
+            "rootlessb" | "b" => VoicingType::RootlessB,
+            _ => VoicingType::Close,
+        }
+    }
+}
+
+/// Range limits and top note target for voicing, as midi note numbers
+#[derive(Clone, Copy)]
+pub struct VoicingOptions {
+    pub low: u8,
+    pub high: u8,
+    pub top: Option<u8>,
+}
+
+impl Default for VoicingOptions {
+    fn default() -> Self {
+        // C3 - C6
+        VoicingOptions {
+            low: 48,
+            high: 84,
+            top: None,
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Voicing {
+    pub chord: String,
+    pub kind: String,
+    pub midi: Vec<u8>,
+    pub notes: Vec<String>,
+}
+
+impl fmt::Display for Voicing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{0:<10} {1:<24} ({2:})",
+            &self.kind,
+            &self.notes.join(" "),
+            &self
+                .midi
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+pub fn supported_voicings() -> Vec<String> {
+    [
+        "close",
+        "open",
+        "drop2",
+        "drop3",
+        "drop24",
+        "spread",
+        "shell",
+        "rootlessa",
+        "rootlessb",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+// Chord tones by function, as semitones above root
+struct Tones {
+    all: Vec<u8>,
+    third: Option<u8>,
+    fifth: Option<u8>,
+    seventh: Option<u8>,
+    ninth: Option<u8>,
+    thirteenth: Option<u8>,
+    // major sixth of chords without seventh, voiced in its place by shells
+    sixth: Option<u8>,
+}
+
+impl Tones {
+    fn new(intervals: &[u8]) -> Tones {
+        let has = |i: u8| intervals.contains(&i);
+        let find = |c: &[u8]| c.iter().find(|&&i| has(i)).copied();
+
+        let third = find(&[4, 3]).or_else(|| find(&[5, 2]));
+        let seventh = find(&[10, 11]).or_else(|| {
+            // diminished 7th
+            if has(3) && has(6) && has(9) {
+                Some(9)
+            } else {
+                None
+            }
+        });
+        let thirteenth = if seventh.is_some() && seventh != Some(9) {
+            find(&[9, 8])
+        } else {
+            None
+        };
+        let sixth = if seventh.is_none() { find(&[9]) } else { None };
+        let ninth = match third {
+            Some(3) => find(&[2, 1]),
+            _ => find(&[2, 1, 3]),
+        };
+
+        let mut all = intervals.to_vec();
+        all.sort_unstable();
+        all.dedup();
+
+        Tones {
+            all,
+            third,
+            fifth: find(&[7, 6, 8]),
+            seventh,
+            ninth,
+            thirteenth,
+            sixth,
+        }
+    }
+}
+
+// Returns close position stack rotated to given inversion
+fn rotate(tones: &[u8], inversion: usize) -> Vec<i32> {
+    let mut v: Vec<i32> = tones[inversion..].iter().map(|&t| t as i32).collect();
+    v.extend(tones[..inversion].iter().map(|&t| t as i32 + 12));
+    v
+}
+
+// Drops voices counted from top (1 = top voice) an octave
+fn drop(stack: &[i32], voices: &[usize]) -> Vec<i32> {
+    let len = stack.len();
+    let mut v: Vec<i32> = stack
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| {
+            if voices.iter().any(|&d| d <= len && len - d == i) {
+                t - 12
+            } else {
+                t
+            }
+        })
+        .collect();
+    v.sort_unstable();
+    v
+}
+
+// Raises every other inner voice an octave
+fn open(stack: &[i32]) -> Vec<i32> {
+    let mut v: Vec<i32> = stack
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| if i % 2 == 1 { t + 12 } else { t })
+        .collect();
+    v.sort_unstable();
+    v
+}
+
+// Returns candidate stacks for voicing type, as semitones above root
+fn candidates(tones: &Tones, kind: VoicingType) -> Vec<Vec<i32>> {
+    let inversions = (0..tones.all.len()).map(|i| rotate(&tones.all, i));
+
+    match kind {
+        VoicingType::Close => inversions.collect(),
+        VoicingType::Open => inversions.map(|s| open(&s)).collect(),
+        VoicingType::Drop2 => inversions.map(|s| drop(&s, &[2])).collect(),
+        VoicingType::Drop3 if tones.all.len() > 3 => inversions.map(|s| drop(&s, &[3])).collect(),
+        VoicingType::Drop24 if tones.all.len() > 3 => {
+            inversions.map(|s| drop(&s, &[2, 4])).collect()
+        }
+        VoicingType::Spread => {
+            // root alone in bass, rest in close position an octave above
+            let mut v = vec![0];
+            v.extend(tones.all[1..].iter().map(|&t| t as i32 + 12));
+            vec![v]
+        }
+        VoicingType::Shell => match (tones.third, tones.seventh.or(tones.sixth)) {
+            (Some(third), Some(seventh)) => vec![
+                vec![0, third as i32, seventh as i32],
+                vec![0, seventh as i32, third as i32 + 12],
+            ],
+            _ => vec![],
+        },
+        VoicingType::RootlessA | VoicingType::RootlessB => match (tones.third, tones.seventh) {
+            (Some(third), Some(seventh)) => {
+                let ninth = tones.ninth.unwrap_or(2) as i32 + 12;
+                let fifth = tones.thirteenth.or(tones.fifth).unwrap_or(7) as i32;
+                let (third, seventh) = (third as i32, seventh as i32);
+
+                if kind == VoicingType::RootlessA {
+                    vec![vec![third, fifth, seventh, ninth]]
+                } else {
+                    vec![vec![seventh, ninth, third + 12, fifth + 12]]
+                }
+            }
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// Returns concrete voicing of chord within given range.
+/// When top note target is set, the inversion and octave
+/// placing the top note closest to target is picked,
+/// otherwise the lowest root position voicing that fits is used.
+pub fn voice(chord: &Chord, kind: VoicingType, options: &VoicingOptions) -> Option<Voicing> {
+    let root = util::pitch_class(chord.root()) as i32;
+    let tones = Tones::new(&chord.intervals());
+    let (low, high) = (options.low as i32, options.high as i32);
+
+    let mut best: Option<(i32, Vec<i32>)> = None;
+
+    for stack in candidates(&tones, kind) {
+        for octave in 0..11 {
+            let midi: Vec<i32> = stack.iter().map(|t| octave * 12 + root + t).collect();
+
+            if midi.iter().any(|&m| m < low || m > high) {
+                continue;
+            }
+
+            let score = match options.top {
+                Some(top) => (midi[midi.len() - 1] - top as i32).abs(),
+                None => 0,
+            };
+
+            if best.as_ref().is_none_or(|b| score < b.0) {
+                best = Some((score, midi));
+            }
+
+            // lowest placement is enough when no target is set
+            if options.top.is_none() {
+                break;
+            }
+        }
+
+        if options.top.is_none() && best.is_some() {
+            break;
+        }
+    }
+
+    best.map(|(_, midi)| Voicing {
+        chord: chord.name.clone(),
+        kind: kind.into(),
+        notes: midi
+            .iter()
+            .map(|&m| util::midi_to_str(m as u8, chord.spell(m as u8)))
+            .collect(),
+        midi: midi.into_iter().map(|m| m as u8).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn midi(intervals: Vec<u8>, kind: VoicingType, options: &VoicingOptions) -> Vec<u8> {
+        let chord = Chord::new("c", intervals, false, 0);
+        voice(&chord, kind, options).unwrap().midi
+    }
+
+    #[test]
+    fn close_and_drop_voicings() {
+        let options = VoicingOptions::default();
+        let maj7 = vec![0, 4, 7, 11];
+        assert_eq!(
+            midi(maj7.clone(), VoicingType::Close, &options),
+            vec![48, 52, 55, 59]
+        );
+        assert_eq!(
+            midi(maj7.clone(), VoicingType::Drop2, &options),
+            vec![55, 60, 64, 71]
+        );
+        assert_eq!(
+            midi(maj7, VoicingType::Spread, &options),
+            vec![48, 64, 67, 71]
+        );
+    }
+
+    #[test]
+    fn top_note_picks_inversion() {
+        let options = VoicingOptions {
+            top: Some(67),
+            ..VoicingOptions::default()
+        };
+        let v = midi(vec![0, 4, 7], VoicingType::Close, &options);
+        assert_eq!(v[v.len() - 1], 67);
+    }
+
+    #[test]
+    fn rootless_voicings_need_third_and_seventh() {
+        let chord = Chord::new("c", vec![0, 4, 7], false, 0);
+        let options = VoicingOptions::default();
+        assert!(voice(&chord, VoicingType::RootlessA, &options).is_none());
+        assert_eq!(
+            midi(vec![0, 3, 7, 10], VoicingType::RootlessA, &options),
+            vec![51, 55, 58, 62]
+        );
+    }
+
+    #[test]
+    fn shell_voices_sixth_without_seventh() {
+        let chord = Chord::new("c", vec![0, 4, 7, 9], false, 0);
+        let v = voice(&chord, VoicingType::Shell, &VoicingOptions::default()).unwrap();
+        assert_eq!(v.midi, vec![48, 52, 57]);
+    }
+
+    #[test]
+    fn shell_voices_seventh() {
+        let chord = Chord::new("g", vec![0, 4, 7, 10], false, 0);
+        let v = voice(&chord, VoicingType::Shell, &VoicingOptions::default()).unwrap();
+        assert_eq!(v.midi, vec![55, 59, 65]);
+    }
+}